@@ -1,3 +1,4 @@
+use crate::gameplay::run::{EndRun, StartRun, StartRunSet};
 use crate::loading::AudioAssets;
 use crate::GameState;
 use bevy::prelude::*;
@@ -10,8 +11,8 @@ impl Plugin for InternalAudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Menu), setup_menu_music)
             .add_systems(OnExit(GameState::Menu), stop_main_menu_music)
-            .add_systems(OnEnter(GameState::GamePlay), setup_gameplay_music)
-            .add_systems(OnExit(GameState::GamePlay), stop_gameplay_music);
+            .add_systems(StartRun, setup_gameplay_music.in_set(StartRunSet::Setup))
+            .add_systems(EndRun, stop_gameplay_music);
     }
 }
 
@@ -19,7 +20,7 @@ impl Plugin for InternalAudioPlugin {
 struct MainMenuMusic(Handle<AudioInstance>);
 
 #[derive(Resource)]
-struct GameplayMusic(Handle<AudioInstance>);
+pub(crate) struct GameplayMusic(pub(crate) Handle<AudioInstance>);
 
 fn setup_menu_music(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
     // audio.pause();
@@ -63,7 +64,7 @@ fn stop_gameplay_music(
 ) {
     if let Some(instance) = audio_instances.get_mut(&main_menu_music.0) {
         match instance.state() {
-            PlaybackState::Playing { .. } => {
+            PlaybackState::Playing { .. } | PlaybackState::Paused { .. } => {
                 instance.stop(AudioTween::default());
             }
             _ => {}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+/// Environment variable read when `--seed` is not given on the command line.
+pub const SEED_ENV_VAR: &str = "MIERDA_SEED";
+
+pub const USAGE: &str =
+    "usage: legend_of_mierda [--seed <n>] [--record <path> | --replay <path>] [--headless [--runs <n>]]";
+
+/// Options passed to the game on the command line.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct CliArgs {
+    /// Seed for all gameplay randomness, a new one is picked every run when not set.
+    pub seed: Option<u64>,
+    /// Record the control events of every run to this replay file.
+    pub record: Option<PathBuf>,
+    /// Play back this replay file instead of reading the keyboard.
+    pub replay: Option<PathBuf>,
+    /// Run without a window or GPU and exit with a summary when the game is over.
+    pub headless: bool,
+    /// Headless runs played one after the other, one when not set.
+    pub runs: Option<u32>,
+}
+
+impl CliArgs {
+    /// Reads `--seed <n>`, `--record <path>`, `--replay <path>` (or `--flag=value`),
+    /// `--headless` and `--runs <n>` from the process arguments, the seed falls back to the
+    /// `MIERDA_SEED` environment variable. Returns a message for the user on bad arguments.
+    pub fn from_env() -> Result<CliArgs, String> {
+        let mut cli_args = CliArgs::default();
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} expects a value", flag))
+            };
+
+            match flag.as_str() {
+                "--seed" => cli_args.seed = Some(parse_seed(&value()?)?),
+                "--record" => cli_args.record = Some(PathBuf::from(value()?)),
+                "--replay" => cli_args.replay = Some(PathBuf::from(value()?)),
+                "--headless" => cli_args.headless = true,
+                "--runs" => cli_args.runs = Some(parse_runs(&value()?)?),
+                _ => return Err(format!("unknown argument {}", flag)),
+            }
+        }
+
+        if cli_args.record.is_some() && cli_args.replay.is_some() {
+            return Err("--record and --replay can't be used together".to_string());
+        }
+
+        if cli_args.runs.is_some() && !cli_args.headless {
+            return Err("--runs only works with --headless".to_string());
+        }
+        if cli_args.runs.is_some_and(|runs| runs > 1) && cli_args.replay.is_some() {
+            return Err("a replay is a single run, --runs can't be used with it".to_string());
+        }
+
+        if cli_args.seed.is_none() {
+            if let Ok(value) = std::env::var(SEED_ENV_VAR) {
+                cli_args.seed = Some(parse_seed(&value)?);
+            }
+        }
+
+        Ok(cli_args)
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("seed must be an unsigned integer, got {:?}", value))
+}
+
+fn parse_runs(value: &str) -> Result<u32, String> {
+    match value.trim().parse() {
+        Ok(runs) if runs > 0 => Ok(runs),
+        _ => Err(format!("runs must be a positive integer, got {:?}", value)),
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// Directory under the platform config dir where user settings are kept.
+const CONFIG_DIR_NAME: &str = "legend_of_mierda";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("no config directory on this platform")]
+    NoConfigDir,
+    #[error("could not access {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not parse {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    #[error("could not serialize {}: {source}", path.display())]
+    Serialize { path: PathBuf, source: ron::Error },
+}
+
+/// Path of a user config file, e.g. `~/.config/legend_of_mierda/input.ron` on Linux.
+pub fn config_path(file_name: &str) -> Result<PathBuf, ConfigError> {
+    dirs::config_dir()
+        .map(|dir| dir.join(CONFIG_DIR_NAME).join(file_name))
+        .ok_or(ConfigError::NoConfigDir)
+}
+
+/// Reads a RON config file, `Ok(None)` if it doesn't exist yet.
+pub fn load<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, ConfigError> {
+    let path = config_path(file_name)?;
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(ConfigError::Io { path, source }),
+    };
+
+    ron::from_str(&contents)
+        .map(Some)
+        .map_err(|source| ConfigError::Parse { path, source })
+}
+
+/// Moves an unreadable config file aside as `<file_name>.bak` so it isn't overwritten.
+pub fn backup(file_name: &str) -> Result<PathBuf, ConfigError> {
+    let path = config_path(file_name)?;
+    let backup_path = path.with_file_name(format!("{}.bak", file_name));
+
+    std::fs::rename(&path, &backup_path).map_err(|source| ConfigError::Io { path, source })?;
+
+    Ok(backup_path)
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) -> Result<(), ConfigError> {
+    let path = config_path(file_name)?;
+
+    let contents =
+        ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(|source| {
+            ConfigError::Serialize {
+                path: path.clone(),
+                source,
+            }
+        })?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|source| ConfigError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+    }
+
+    std::fs::write(&path, contents).map_err(|source| ConfigError::Io { path, source })
+}
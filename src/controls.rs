@@ -1,20 +1,55 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_magic_light_2d::SpriteCamera;
 use bevy_rapier2d::prelude::*;
 use pecs::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    entities::player::{Player, PlayerAttackEvent},
+    entities::{
+        health::Dead,
+        player::{Player, PlayerAttackEvent},
+        status_effects::StatusEffects,
+    },
+    gameplay::progression::PlayerStats,
+    input_map::{ActionInput, InputAction, InputSource, Targeting},
     loading::CharacterSpritesheets,
     sprites::*,
 };
 
-#[derive(Event, Copy, Clone, Reflect, Debug, PartialEq, Eq, Default)]
+/// Radial deadzone of the gamepad sticks, as a fraction of full deflection.
+pub const STICK_DEADZONE: f32 = 0.2;
+
+#[derive(Event, Copy, Clone, Reflect, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct ControlEvent {
     pub up: bool,
     pub down: bool,
     pub left: bool,
     pub right: bool,
     pub attack: bool,
+    /// Analog movement from a gamepad stick, its length is the speed from 0 to 1.
+    /// Overrides the direction flags when set.
+    #[serde(default)]
+    pub analog: Option<Vec2>,
+    /// Direction the speargun points in, from the mouse cursor or the right stick
+    /// depending on the [`Targeting`] mode.
+    #[serde(default)]
+    pub aim: Option<Vec2>,
+    /// Turns the speargun with the aim buttons, positive is counterclockwise.
+    #[serde(default)]
+    pub turn_aim: f32,
+}
+
+/// Gamepad that controls the player, picked on connect and released on disconnect.
+#[derive(Resource, Default, Debug)]
+pub struct PlayerGamepad(pub Option<Gamepad>);
+
+/// Systems sending [`ControlEvent`]s run in `Input`, the ones reading them in `Apply`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ControlSet {
+    Input,
+    Apply,
 }
 
 pub fn control_character(
@@ -27,16 +62,32 @@ pub fn control_character(
             &mut Velocity,
             &mut CharacterAnimation,
             &mut TextureAtlasSprite,
-            &Player,
+            Option<&StatusEffects>,
         ),
-        With<Player>,
+        (With<Player>, Without<Dead>),
     >,
     spritesheets: Res<CharacterSpritesheets>,
+    stats: Res<PlayerStats>,
 ) {
     for control in ev_control.read() {
-        for (entity, mut texture_atlas, mut velocity, mut char_animation, mut sprite, _player) in
-            &mut query
+        for (
+            entity,
+            mut texture_atlas,
+            mut velocity,
+            mut char_animation,
+            mut sprite,
+            status_effects,
+        ) in &mut query
         {
+            let speed_multiplier = match status_effects {
+                Some(effects) if effects.is_stunned() => {
+                    velocity.linvel = Vec2::ZERO;
+                    continue;
+                }
+                Some(effects) => effects.speed_multiplier(),
+                None => 1.0,
+            };
+
             if control.attack {
                 char_animation.animation_type = AnimationType::Attack;
                 texture_atlas.clone_from(&spritesheets.player_atlas_2);
@@ -63,10 +114,11 @@ pub fn control_character(
                 let up = if control.up { 1. } else { 0. };
                 let down = if control.down { 1. } else { 0. };
 
-                velocity.linvel.x = right - left;
-                velocity.linvel.y = up - down;
-
-                velocity.linvel = velocity.linvel.normalize_or_zero() * 100.;
+                velocity.linvel = match control.analog {
+                    Some(analog) => analog.clamp_length_max(1.0),
+                    None => Vec2::new(right - left, up - down).normalize_or_zero(),
+                } * stats.move_speed
+                    * speed_multiplier;
 
                 let linvel_norm = velocity.linvel.distance(Vec2::ZERO);
 
@@ -101,13 +153,136 @@ pub fn control_character(
     }
 }
 
-pub fn keyboard_controls(input: Res<Input<KeyCode>>, mut ev_control: EventWriter<ControlEvent>) {
+/// Direction from the player to the mouse cursor, in world space.
+fn cursor_aim(
+    q_window: &Query<&Window, With<PrimaryWindow>>,
+    q_camera: &Query<(&Camera, &GlobalTransform), With<SpriteCamera>>,
+    q_player: &Query<&GlobalTransform, With<Player>>,
+) -> Option<Vec2> {
+    let window = q_window.get_single().ok()?;
+    let (camera, camera_transform) = q_camera.get_single().ok()?;
+    let player_transform = q_player.get_single().ok()?;
+
+    let cursor_position = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())?;
+
+    let aim = cursor_position - player_transform.translation().truncate();
+    (aim != Vec2::ZERO).then(|| aim.normalize())
+}
+
+pub fn keyboard_controls(
+    input: ActionInput,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<SpriteCamera>>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut ev_control: EventWriter<ControlEvent>,
+) {
     let mut control = ControlEvent { ..default() };
+    let keyboard = InputSource::Keyboard;
+    let pressed = |action| input.pressed_on(keyboard, action) as i32 as f32;
 
-    control.right = input.pressed(KeyCode::D);
-    control.left = input.pressed(KeyCode::A);
-    control.up = input.pressed(KeyCode::W);
-    control.down = input.pressed(KeyCode::S);
+    control.right = input.pressed_on(keyboard, InputAction::MoveRight);
+    control.left = input.pressed_on(keyboard, InputAction::MoveLeft);
+    control.up = input.pressed_on(keyboard, InputAction::MoveUp);
+    control.down = input.pressed_on(keyboard, InputAction::MoveDown);
+    control.attack = input.just_pressed_on(keyboard, InputAction::Attack);
+    control.turn_aim = pressed(InputAction::AimLeft) - pressed(InputAction::AimRight);
+
+    if input.targeting() == Targeting::Mouse {
+        control.aim = cursor_aim(&q_window, &q_camera, &q_player);
+    }
 
     ev_control.send(control);
 }
+
+pub fn handle_gamepad_connections(
+    mut player_gamepad: ResMut<PlayerGamepad>,
+    mut er_connection: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+) {
+    for event in er_connection.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                if player_gamepad.0.is_none() {
+                    info!(
+                        "gamepad {} ({}) controls the player",
+                        event.gamepad.id, info.name
+                    );
+                    player_gamepad.0 = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if player_gamepad.0 == Some(event.gamepad) {
+                    // hand the player over to another connected gamepad, if any
+                    player_gamepad.0 = gamepads.iter().find(|g| *g != event.gamepad);
+                    info!("gamepad {} disconnected", event.gamepad.id);
+                }
+            }
+        }
+    }
+}
+
+pub fn gamepad_controls(
+    player_gamepad: Res<PlayerGamepad>,
+    axes: Res<Axis<GamepadAxis>>,
+    input: ActionInput,
+    mut ev_control: EventWriter<ControlEvent>,
+) {
+    let Some(gamepad) = player_gamepad.0 else {
+        return;
+    };
+
+    let pressed = |action| input.pressed_on(InputSource::Gamepad, action) as i32 as f32;
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+
+    let stick = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    );
+    let dpad = Vec2::new(
+        pressed(InputAction::MoveRight) - pressed(InputAction::MoveLeft),
+        pressed(InputAction::MoveUp) - pressed(InputAction::MoveDown),
+    );
+
+    let analog = if dpad != Vec2::ZERO {
+        dpad.normalize()
+    } else if stick.length() > STICK_DEADZONE {
+        // rescale so movement starts from zero at the edge of the deadzone
+        stick.normalize() * ((stick.length() - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)).min(1.0)
+    } else {
+        Vec2::ZERO
+    };
+
+    let attack = input.just_pressed_on(InputSource::Gamepad, InputAction::Attack);
+    let turn_aim = pressed(InputAction::AimLeft) - pressed(InputAction::AimRight);
+
+    let right_stick = Vec2::new(
+        axis(GamepadAxisType::RightStickX),
+        axis(GamepadAxisType::RightStickY),
+    );
+    let aim = match input.targeting() {
+        Targeting::Stick if right_stick.length() > STICK_DEADZONE => Some(right_stick.normalize()),
+        _ => None,
+    };
+
+    // an idle gamepad leaves the player to the keyboard
+    if analog == Vec2::ZERO && !attack && aim.is_none() && turn_aim == 0.0 {
+        return;
+    }
+
+    ev_control.send(ControlEvent {
+        up: analog.y > 0.0,
+        down: analog.y < 0.0,
+        left: analog.x < 0.0,
+        right: analog.x > 0.0,
+        attack,
+        analog: Some(analog),
+        aim,
+        turn_aim,
+    });
+}
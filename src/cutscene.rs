@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use crate::loading::{AvatarAssets, CutsceneAssets, FontAssets};
+use crate::gameplay::campaign::{Campaign, CampaignProgress, DialogLine, Speaker};
+use crate::loading::{AvatarAssets, CampaignAssets, CutsceneAssets, FontAssets};
+use crate::pause::GamePause;
 use crate::GameState;
 use bevy::prelude::*;
 
@@ -10,6 +12,18 @@ pub struct CutscenePlugin;
 struct CutsceneState {
     timer: Timer,
     timer_count: usize,
+    /// Dialog of the campaign level the cutscene is played for.
+    dialog: Vec<DialogLine>,
+}
+
+impl Default for CutsceneState {
+    fn default() -> Self {
+        CutsceneState {
+            timer: Timer::new(Duration::from_secs(3), TimerMode::Repeating),
+            timer_count: 0,
+            dialog: vec![],
+        }
+    }
 }
 
 #[derive(Component)]
@@ -44,21 +58,34 @@ impl Plugin for CutscenePlugin {
             (handle_cutscene_text, handle_cutscene_termination)
                 .run_if(in_state(GameState::Cutscene)),
         )
-        .insert_resource(CutsceneState {
-            timer: Timer::new(Duration::from_secs(3), TimerMode::Repeating),
-            timer_count: 0,
-        });
+        .init_resource::<CutsceneState>();
     }
 }
 
 fn setup_cutscene(
     mut commands: Commands,
+    mut cutscene_state: ResMut<CutsceneState>,
     avatar_assets: Res<AvatarAssets>,
     cutscene_assets: Res<CutsceneAssets>,
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+    progress: Res<CampaignProgress>,
     font_assets: Res<FontAssets>,
 ) {
     info!("cutscene");
 
+    let level = campaigns
+        .get(&campaign_assets.campaign)
+        .and_then(|campaign| campaign.levels.get(progress.level_index));
+
+    *cutscene_state = CutsceneState {
+        dialog: level
+            .map(|level| level.cutscene.clone())
+            .unwrap_or_default(),
+        ..default()
+    };
+    let title = level.map(|level| level.title.clone()).unwrap_or_default();
+
     commands
         .spawn((
             NodeBundle {
@@ -71,6 +98,8 @@ fn setup_cutscene(
                     ..default()
                 },
                 background_color: Color::BLACK.into(),
+                // above the game UI when played between two levels
+                z_index: ZIndex::Global(98),
                 ..default()
             },
             Cutscene,
@@ -164,7 +193,7 @@ fn setup_cutscene(
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "   PRISON CPS 17\nMEXICO, BUENAVISTA",
+                    title,
                     TextStyle {
                         font: font_assets.pixeloid_mono.clone(),
                         font_size: 40.0,
@@ -186,6 +215,7 @@ fn setup_cutscene(
                     align_items: AlignItems::Center,
                     ..default()
                 },
+                z_index: ZIndex::Global(99),
                 ..default()
             },
             Cutscene,
@@ -213,18 +243,16 @@ fn cleanup_cutscene(mut commands: Commands, menu: Query<Entity, With<Cutscene>>)
     }
 }
 
-fn handle_cutscene_termination(
-    mut next_state: ResMut<NextState<GameState>>,
-    cutscene_state: ResMut<CutsceneState>,
-) {
-    let cutscene_text = get_cutscene_dialog_text();
-    if cutscene_text.len() <= cutscene_state.timer_count {
-        next_state.set(GameState::GamePlay);
+fn handle_cutscene_termination(mut game_pause: GamePause, cutscene_state: ResMut<CutsceneState>) {
+    if cutscene_state.dialog.len() <= cutscene_state.timer_count {
+        // also unfreezes the run when the cutscene is played between two levels
+        game_pause.resume();
     }
 }
 
 fn handle_cutscene_text(
-    time: Res<Time>,
+    // real time, a cutscene between two levels plays while the run is frozen
+    time: Res<Time<Real>>,
     mut cutscene_state: ResMut<CutsceneState>,
     mut query: Query<(&mut Text, &CutsceneDialogText)>,
     mut avatar_set: ParamSet<(
@@ -238,55 +266,24 @@ fn handle_cutscene_text(
         cutscene_state.timer_count += 1;
     }
 
-    let cutscene_text = get_cutscene_dialog_text();
-    if cutscene_text.len() <= cutscene_state.timer_count {
+    let Some(line) = cutscene_state.dialog.get(cutscene_state.timer_count) else {
         return;
-    }
-
-    let (index, text) = cutscene_text[cutscene_state.timer_count].clone();
+    };
 
     for (mut visibility, _) in avatar_set.p0().iter_mut() {
-        *visibility = match index {
-            0 => Visibility::Visible,
+        *visibility = match line.speaker {
+            Speaker::Alextime => Visibility::Visible,
             _ => Visibility::Hidden,
         };
     }
     for (mut visibility, _) in avatar_set.p1().iter_mut() {
-        *visibility = match index {
-            1 => Visibility::Visible,
+        *visibility = match line.speaker {
+            Speaker::Gennadiy => Visibility::Visible,
             _ => Visibility::Hidden,
         };
     }
 
     for (mut text_component, _) in query.iter_mut() {
-        text_component.sections[0].value = text.clone();
+        text_component.sections[0].value = line.text.clone();
     }
 }
-
-fn get_cutscene_dialog_text() -> Vec<(usize, String)> {
-    vec![
-        (
-            1,
-            "Your Highness, they've charged you with smuggling red caviar into the mess hall."
-                .to_string(),
-        ),
-        (
-            0,
-            "Ah, Gena, they simply cannot resist my gourmet diplomacy".to_string(),
-        ),
-        (
-            1,
-            "Diplomacy, sire? It's more like high-seas gastronomy.".to_string(),
-        ),
-        (
-            0,
-            "Fear not, Gena. If they lock me up, I'll become the Cell Block Caviar Tsar!"
-                .to_string(),
-        ),
-        (
-            1,
-            "Your platform: From the Elecrostahl to the cell, promising red caviar for all!"
-                .to_string(),
-        ),
-    ]
-}
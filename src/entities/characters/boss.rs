@@ -0,0 +1,410 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    entities::{
+        health::Health, player::Player, text_indicator::SpawnTextIndicatorEvent,
+        weapons::pill::PillBarrageEvent,
+    },
+    gameplay::{
+        run::{reset_resource, StartRun, StartRunSet},
+        spawn_pattern::SpawnPattern,
+        waves::{GameplayState, WaveEntry},
+    },
+    ui::{UIBossBar, UIBossHealth, UIBossName},
+    GameState,
+};
+
+use super::brain::steer_enemies;
+use super::enemy::{Enemy, EnemyType, SpawnEnemyEvent};
+
+/// Seconds a boss stands still while its health bar fills up.
+const INTRO_SECONDS: f32 = 2.5;
+/// Seconds between the death of the last boss and the fight counting as won.
+const DEFEAT_SECONDS: f32 = 3.0;
+
+// ------
+// Phases
+// ------
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BossAttack {
+    /// Pills thrown in every direction at once.
+    PillBarrage { pills: usize },
+    /// Mierdas spawned around the player.
+    Summon { count: u32 },
+    /// A dash towards the player.
+    Charge { speed: f32, seconds: f32 },
+}
+
+pub struct BossPhase {
+    /// The phase starts once the boss health drops to this fraction of its maximum.
+    pub health_fraction: f32,
+    /// Seconds between two attacks.
+    pub attack_interval: f32,
+    /// Attacks used in turn.
+    pub attacks: &'static [BossAttack],
+}
+
+pub const BOSS_PHASES: [BossPhase; 3] = [
+    BossPhase {
+        health_fraction: 1.0,
+        attack_interval: 3.0,
+        attacks: &[BossAttack::PillBarrage { pills: 12 }],
+    },
+    BossPhase {
+        health_fraction: 0.66,
+        attack_interval: 2.5,
+        attacks: &[
+            BossAttack::PillBarrage { pills: 16 },
+            BossAttack::Summon { count: 20 },
+            BossAttack::PillBarrage { pills: 16 },
+        ],
+    },
+    BossPhase {
+        health_fraction: 0.33,
+        attack_interval: 1.5,
+        attacks: &[
+            BossAttack::Charge {
+                speed: 600.0,
+                seconds: 0.6,
+            },
+            BossAttack::PillBarrage { pills: 24 },
+            BossAttack::Charge {
+                speed: 600.0,
+                seconds: 0.6,
+            },
+            BossAttack::Summon { count: 30 },
+        ],
+    },
+];
+
+pub fn boss_name(enemy_type: EnemyType) -> &'static str {
+    match enemy_type {
+        EnemyType::Mierda => "LA MIERDA",
+        EnemyType::Pendejo => "EL PENDEJO",
+        EnemyType::Psychiatrist1 => "PSIQUIATRA I",
+        EnemyType::Psychiatrist2 => "PSIQUIATRA II",
+    }
+}
+
+// ----------
+// Components
+// ----------
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum BossState {
+    /// Standing still while the health bar fills up.
+    Intro(Timer),
+    Fighting,
+    Charging {
+        velocity: Vec2,
+        timer: Timer,
+    },
+    Defeated,
+}
+
+/// Enemy spawned by a [`WaveEntry::Boss`], on top of its usual AI it attacks according
+/// to the [`BossPhase`] of its health.
+#[derive(Clone, PartialEq, Debug, Component)]
+pub struct Boss {
+    /// Index into [`BOSS_PHASES`].
+    pub phase: usize,
+    pub state: BossState,
+    attack_timer: Timer,
+    next_attack: usize,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Boss {
+            phase: 0,
+            state: BossState::Intro(Timer::from_seconds(INTRO_SECONDS, TimerMode::Once)),
+            attack_timer: Timer::from_seconds(BOSS_PHASES[0].attack_interval, TimerMode::Repeating),
+            next_attack: 0,
+        }
+    }
+}
+
+impl Boss {
+    /// Index of the phase the boss is in with `health` left.
+    pub fn phase_for(health: &Health) -> usize {
+        BOSS_PHASES
+            .iter()
+            .rposition(|phase| health.fraction() <= phase.health_fraction)
+            .unwrap_or(0)
+    }
+}
+
+// ---------
+// Resources
+// ---------
+
+#[derive(Resource, Default)]
+pub struct BossFight {
+    /// Running from the death of the last boss until [`BossDefeatedEvent`] is sent.
+    pub defeat_timer: Option<Timer>,
+}
+
+// ------
+// Events
+// ------
+
+/// Sent once every boss of the level is dead and the defeat sequence is over.
+#[derive(Event, Clone)]
+pub struct BossDefeatedEvent;
+
+// -------
+// Systems
+// -------
+
+fn announce_bosses(
+    q_bosses: Query<(Entity, &Enemy), Added<Boss>>,
+    mut ew_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    for (entity, enemy) in q_bosses.iter() {
+        ew_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+            text: boss_name(enemy.enemy_type).to_string(),
+            entity,
+        });
+    }
+}
+
+fn update_boss_phases(
+    mut q_bosses: Query<(Entity, &Health, &mut Boss)>,
+    mut ew_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    for (entity, health, mut boss) in q_bosses.iter_mut() {
+        if matches!(boss.state, BossState::Intro(_) | BossState::Defeated) {
+            continue;
+        }
+
+        // phases only go forward, healing doesn't calm a boss down
+        let phase = Boss::phase_for(health);
+        if phase <= boss.phase {
+            continue;
+        }
+
+        boss.phase = phase;
+        boss.next_attack = 0;
+        boss.attack_timer =
+            Timer::from_seconds(BOSS_PHASES[phase].attack_interval, TimerMode::Repeating);
+
+        ew_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+            text: format!("FASE {}", phase + 1),
+            entity,
+        });
+    }
+}
+
+fn boss_attacks(
+    time: Res<Time>,
+    mut q_bosses: Query<(Entity, &Transform, &Health, &mut Boss)>,
+    q_player: Query<&Transform, With<Player>>,
+    mut ew_pill_barrage: EventWriter<PillBarrageEvent>,
+    mut ew_spawn_enemy: EventWriter<SpawnEnemyEvent>,
+) {
+    let Ok(player_transform) = q_player.get_single() else {
+        return;
+    };
+
+    for (entity, transform, health, mut boss) in q_bosses.iter_mut() {
+        if health.is_dead() {
+            continue;
+        }
+
+        match &mut boss.state {
+            BossState::Intro(timer) | BossState::Charging { timer, .. } => {
+                if timer.tick(time.delta()).finished() {
+                    boss.state = BossState::Fighting;
+                }
+                continue;
+            }
+            BossState::Fighting => {}
+            BossState::Defeated => continue,
+        }
+
+        if !boss.attack_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let attacks = BOSS_PHASES[boss.phase].attacks;
+        let attack = attacks[boss.next_attack % attacks.len()];
+        boss.next_attack += 1;
+
+        match attack {
+            BossAttack::PillBarrage { pills } => {
+                ew_pill_barrage.send(PillBarrageEvent { entity, pills });
+            }
+            BossAttack::Summon { count } => {
+                ew_spawn_enemy.send(SpawnEnemyEvent {
+                    count,
+                    enemy_type: EnemyType::Mierda,
+                    pattern: SpawnPattern::RingAroundPlayer {
+                        min_radius: 150.0,
+                        max_radius: 300.0,
+                    },
+                    boss: false,
+                });
+            }
+            BossAttack::Charge { speed, seconds } => {
+                let direction = (player_transform.translation - transform.translation)
+                    .truncate()
+                    .normalize_or_zero();
+
+                boss.state = BossState::Charging {
+                    velocity: direction * speed,
+                    timer: Timer::from_seconds(seconds, TimerMode::Once),
+                };
+            }
+        }
+    }
+}
+
+/// Overrides the chase of the boss AI while a boss is not free to move.
+fn boss_movement(mut q_bosses: Query<(&mut Velocity, &Boss)>) {
+    for (mut velocity, boss) in q_bosses.iter_mut() {
+        match &boss.state {
+            BossState::Intro(_) | BossState::Defeated => velocity.linvel = Vec2::ZERO,
+            BossState::Charging {
+                velocity: charge, ..
+            } => velocity.linvel = *charge,
+            BossState::Fighting => {}
+        }
+    }
+}
+
+fn check_bosses_defeated(
+    mut q_bosses: Query<(Entity, &Health, &mut Boss)>,
+    gameplay_state: Res<GameplayState>,
+    mut boss_fight: ResMut<BossFight>,
+    mut ew_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    let mut boss_died = false;
+
+    for (entity, health, mut boss) in q_bosses.iter_mut() {
+        if !health.is_dead() || boss.state == BossState::Defeated {
+            continue;
+        }
+
+        boss.state = BossState::Defeated;
+        boss_died = true;
+
+        ew_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+            text: "DERROTADO".to_string(),
+            entity,
+        });
+    }
+
+    let bosses_left = q_bosses
+        .iter()
+        .any(|(_, _, boss)| boss.state != BossState::Defeated);
+    let bosses_queued = gameplay_state
+        .event_queue
+        .iter()
+        .any(|entry| matches!(entry, WaveEntry::Boss { .. }));
+
+    if boss_died && !bosses_left && !bosses_queued {
+        boss_fight.defeat_timer = Some(Timer::from_seconds(DEFEAT_SECONDS, TimerMode::Once));
+    }
+}
+
+fn tick_boss_defeat(
+    time: Res<Time>,
+    mut boss_fight: ResMut<BossFight>,
+    mut ew_boss_defeated: EventWriter<BossDefeatedEvent>,
+) {
+    let Some(timer) = boss_fight.defeat_timer.as_mut() else {
+        return;
+    };
+
+    if timer.tick(time.delta()).finished() {
+        boss_fight.defeat_timer = None;
+        ew_boss_defeated.send(BossDefeatedEvent);
+    }
+}
+
+fn update_boss_health_bar(
+    q_bosses: Query<(&Enemy, &Health, &Boss)>,
+    boss_fight: Res<BossFight>,
+    mut q_bar: Query<&mut Visibility, With<UIBossBar>>,
+    mut q_name: Query<&mut Text, With<UIBossName>>,
+    mut q_health: Query<&mut Style, With<UIBossHealth>>,
+) {
+    let bosses = q_bosses
+        .iter()
+        .filter(|(_, _, boss)| boss.state != BossState::Defeated)
+        .collect::<Vec<_>>();
+
+    let (name, fill) = if !bosses.is_empty() {
+        let health: f32 = bosses
+            .iter()
+            .map(|(_, health, _)| health.current as f32)
+            .sum();
+        let max_health: f32 = bosses.iter().map(|(_, health, _)| health.max as f32).sum();
+
+        // the bar fills up during the intro
+        let intro = bosses
+            .iter()
+            .filter_map(|(_, _, boss)| match &boss.state {
+                BossState::Intro(timer) => Some(timer.percent()),
+                _ => None,
+            })
+            .fold(1.0, f32::min);
+
+        let names = bosses
+            .iter()
+            .map(|(enemy, _, _)| boss_name(enemy.enemy_type))
+            .collect::<Vec<_>>()
+            .join(" & ");
+
+        (names, intro * health / max_health.max(1.0))
+    } else if boss_fight.defeat_timer.is_some() {
+        ("DERROTADO".to_string(), 0.0)
+    } else {
+        for mut visibility in q_bar.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    for mut visibility in q_bar.iter_mut() {
+        *visibility = Visibility::Visible;
+    }
+    for mut text in q_name.iter_mut() {
+        text.sections[0].value = name.clone();
+    }
+    for mut style in q_health.iter_mut() {
+        style.width = Val::Percent(100.0 * fill);
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossFight>()
+            .add_event::<BossDefeatedEvent>()
+            .add_systems(
+                StartRun,
+                reset_resource::<BossFight>.in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    announce_bosses,
+                    update_boss_phases,
+                    boss_attacks.after(update_boss_phases),
+                    boss_movement.after(boss_attacks).after(steer_enemies),
+                    check_bosses_defeated,
+                    tick_boss_defeat.after(check_bosses_defeated),
+                    update_boss_health_bar.after(check_bosses_defeated),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
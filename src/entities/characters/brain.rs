@@ -0,0 +1,355 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    entities::{
+        health::Dead,
+        player::Player,
+        status_effects::{event_apply_status_effect, StatusEffects},
+    },
+    gameplay::{
+        navigation::{self, Navigation},
+        rng::{GameRng, GameRngSet},
+    },
+    GameState,
+};
+
+use super::definition::{EnemyDefinition, EnemyDefinitions};
+use super::enemy::Enemy;
+
+/// Most the `Wander` heading turns between two decisions, in radians.
+const WANDER_TURN: f32 = 0.5;
+
+// ---------
+// Steering
+// ---------
+
+/// A steering behaviour, the direction an enemy wants to go is the sum of its behaviours.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Steering {
+    /// Towards the player, around walls.
+    Seek { weight: f32 },
+    /// Away from the player while closer than `radius`.
+    Flee { weight: f32, radius: f32 },
+    /// Around the player, `radius` pixels away.
+    Orbit { weight: f32, radius: f32 },
+    /// Towards the player, around walls, when further than `distance`, away when closer.
+    KeepDistance { weight: f32, distance: f32 },
+    /// A heading that turns a little at random on every decision.
+    Wander { weight: f32 },
+    /// Away from other enemies closer than `radius`.
+    Separation { weight: f32, radius: f32 },
+}
+
+/// What an enemy knows about its surroundings when deciding where to go.
+pub struct SteeringContext<'a> {
+    pub position: Vec2,
+    pub player_position: Vec2,
+    /// Direction towards the player around the walls in the way.
+    pub path_direction: Vec2,
+    pub wander_angle: f32,
+    /// Positions of the other enemies.
+    pub neighbours: &'a [Vec2],
+}
+
+impl Steering {
+    pub fn steer(&self, context: &SteeringContext) -> Vec2 {
+        let to_player = context.player_position - context.position;
+        let distance = to_player.length();
+        let direction = to_player.normalize_or_zero();
+
+        match *self {
+            Steering::Seek { weight } => weight * context.path_direction,
+            Steering::Flee { weight, radius } if distance < radius => {
+                -weight * (1.0 - distance / radius) * direction
+            }
+            Steering::Flee { .. } => Vec2::ZERO,
+            Steering::Orbit { weight, radius } => {
+                let correction = ((distance - radius) / radius).clamp(-1.0, 1.0);
+                weight * (direction.perp() + correction * direction).normalize_or_zero()
+            }
+            Steering::KeepDistance {
+                weight,
+                distance: kept,
+            } => {
+                let approach = ((distance - kept) / kept).clamp(-1.0, 1.0);
+                // backing off takes the straight line, closing in goes around walls
+                let heading = if distance > kept {
+                    context.path_direction
+                } else {
+                    direction
+                };
+                weight * approach * heading
+            }
+            Steering::Wander { weight } => weight * Vec2::from_angle(context.wander_angle),
+            Steering::Separation { weight, radius } => {
+                let push: Vec2 = context
+                    .neighbours
+                    .iter()
+                    .map(|neighbour| context.position - *neighbour)
+                    .filter(|away| away.length() > 0.0 && away.length() < radius)
+                    .map(|away| away.normalize() * (1.0 - away.length() / radius))
+                    .sum();
+                weight * push
+            }
+        }
+    }
+}
+
+// ----------
+// Definition
+// ----------
+
+/// How an enemy type moves, part of its [`EnemyDefinition`](super::definition::EnemyDefinition).
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct BrainDefinition {
+    /// Pixels per second.
+    pub speed: f32,
+    /// Seconds between two decisions on where to go.
+    pub think_interval: f32,
+    /// The heading swings back and forth by up to this many radians.
+    #[serde(default)]
+    pub sway: f32,
+    pub behaviours: Vec<Steering>,
+}
+
+pub fn validate_brain(brain: &BrainDefinition) -> Result<(), &'static str> {
+    if !(brain.speed.is_finite() && brain.speed >= 0.0) {
+        return Err("speed must be a positive number");
+    }
+    if !(brain.think_interval.is_finite() && brain.think_interval > 0.0) {
+        return Err("think_interval must be a positive number of seconds");
+    }
+    if brain.behaviours.is_empty() {
+        return Err("brain has no behaviours");
+    }
+
+    Ok(())
+}
+
+// ----------
+// Components
+// ----------
+
+/// Decides where a non-dummy enemy goes, the result is written to
+/// [`Enemy::move_direction`].
+#[derive(Component, Clone, Debug)]
+pub struct EnemyBrain {
+    pub definition: BrainDefinition,
+    think_timer: Timer,
+    wander_angle: f32,
+}
+
+impl EnemyBrain {
+    pub fn new(definition: BrainDefinition) -> Self {
+        let mut think_timer = Timer::from_seconds(definition.think_interval, TimerMode::Repeating);
+        // decide right away instead of standing still for the first interval
+        think_timer.set_elapsed(think_timer.duration());
+
+        EnemyBrain {
+            definition,
+            think_timer,
+            wander_angle: 0.0,
+        }
+    }
+}
+
+// -------
+// Systems
+// -------
+
+fn attach_enemy_brains(
+    mut commands: Commands,
+    enemy_definitions: EnemyDefinitions,
+    q_enemies: Query<(Entity, &Enemy), Without<EnemyBrain>>,
+) {
+    for (entity, enemy) in q_enemies.iter().filter(|(_, enemy)| !enemy.is_dummy) {
+        let definition = enemy_definitions.get(enemy.enemy_type).brain.clone();
+        commands.entity(entity).insert(EnemyBrain::new(definition));
+    }
+}
+
+/// Picks up edits of the enemy definitions made while the game is running.
+fn reload_enemy_brains(
+    mut er_enemy_definition: EventReader<AssetEvent<EnemyDefinition>>,
+    enemy_definitions: EnemyDefinitions,
+    mut q_brains: Query<(&Enemy, &mut EnemyBrain)>,
+) {
+    if !er_enemy_definition
+        .read()
+        .any(|event| matches!(event, AssetEvent::Modified { .. }))
+    {
+        return;
+    }
+
+    for (enemy, mut brain) in q_brains.iter_mut() {
+        let definition = &enemy_definitions.get(enemy.enemy_type).brain;
+        if brain.definition != *definition {
+            *brain = EnemyBrain::new(definition.clone());
+        }
+    }
+}
+
+pub fn think(
+    time: Res<Time>,
+    mut game_rng: ResMut<GameRng>,
+    navigation: Res<Navigation>,
+    q_player: Query<&Transform, With<Player>>,
+    // dying enemies don't move and aren't in the way
+    mut q_enemies: Query<(&Transform, &mut Enemy, &mut EnemyBrain), Without<Dead>>,
+) {
+    let Ok(player_transform) = q_player.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+
+    let neighbours = q_enemies
+        .iter()
+        .map(|(transform, _, _)| transform.translation.truncate())
+        .collect::<Vec<_>>();
+
+    for (transform, mut enemy, mut brain) in q_enemies.iter_mut() {
+        if !brain.think_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        brain.wander_angle += game_rng.gen_range(-WANDER_TURN..=WANDER_TURN);
+
+        let position = transform.translation.truncate();
+        let context = SteeringContext {
+            position,
+            player_position,
+            path_direction: navigation.direction_to_player(position, player_position),
+            wander_angle: brain.wander_angle,
+            neighbours: &neighbours,
+        };
+
+        enemy.move_direction = brain
+            .definition
+            .behaviours
+            .iter()
+            .map(|behaviour| behaviour.steer(&context))
+            .sum::<Vec2>()
+            .normalize_or_zero();
+    }
+}
+
+/// Moves enemies along their `move_direction`, unless they are stunned, at the speed their
+/// status effects leave them.
+pub fn steer_enemies(
+    time: Res<Time>,
+    mut q_enemies: Query<
+        (&mut Velocity, &Enemy, &EnemyBrain, Option<&StatusEffects>),
+        Without<Dead>,
+    >,
+) {
+    for (mut velocity, enemy, brain, status_effects) in q_enemies.iter_mut() {
+        let speed_multiplier = match status_effects {
+            Some(effects) if effects.is_stunned() => continue,
+            Some(effects) => effects.speed_multiplier(),
+            None => 1.0,
+        };
+
+        let sway = time.elapsed_seconds().cos() * brain.definition.sway;
+        velocity.linvel = Vec2::from_angle(sway).rotate(enemy.move_direction)
+            * brain.definition.speed
+            * speed_multiplier;
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct BrainPlugin;
+
+impl Plugin for BrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                reload_enemy_brains,
+                attach_enemy_brains,
+                think
+                    .in_set(GameRngSet::EnemyBrains)
+                    .after(attach_enemy_brains)
+                    .after(navigation::update_flow_field),
+                steer_enemies.after(think).after(event_apply_status_effect),
+            )
+                .run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::navigation::{FlowField, NavigationGrid};
+
+    const CELL: f32 = 16.0;
+
+    fn center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * CELL
+    }
+
+    /// A wall between the enemy and the player with a gap at the top.
+    fn walled_grid() -> NavigationGrid {
+        let mut grid = NavigationGrid::new(10, 10, CELL);
+        for y in 0..8 {
+            grid.set_wall(IVec2::new(5, y));
+        }
+        grid
+    }
+
+    #[test]
+    fn keep_distance_goes_around_walls() {
+        let grid = walled_grid();
+        let enemy_cell = IVec2::new(2, 2);
+        let player_cell = IVec2::new(8, 2);
+        let flow_field = FlowField::new(&grid, player_cell);
+        let path_direction = flow_field.direction(&grid, enemy_cell).unwrap();
+
+        let context = SteeringContext {
+            position: center(enemy_cell),
+            player_position: center(player_cell),
+            path_direction,
+            wander_angle: 0.0,
+            neighbours: &[],
+        };
+        let steering = Steering::KeepDistance {
+            weight: 1.0,
+            distance: 32.0,
+        };
+        let heading = steering.steer(&context).normalize();
+
+        // straight at the player would walk into the wall, the way around is up
+        assert!(!grid.line_of_sight(context.position, context.player_position));
+        assert!(heading.y > 0.0);
+        assert!(heading.abs_diff_eq(path_direction, 1e-5));
+    }
+
+    #[test]
+    fn keep_distance_backs_off_in_a_straight_line() {
+        let grid = walled_grid();
+        let flow_field = FlowField::new(&grid, IVec2::new(8, 2));
+
+        let context = SteeringContext {
+            position: center(IVec2::new(7, 2)),
+            player_position: center(IVec2::new(8, 2)),
+            path_direction: flow_field.direction(&grid, IVec2::new(7, 2)).unwrap(),
+            wander_angle: 0.0,
+            neighbours: &[],
+        };
+        let steering = Steering::KeepDistance {
+            weight: 1.0,
+            distance: 64.0,
+        };
+
+        assert!(steering
+            .steer(&context)
+            .normalize()
+            .abs_diff_eq(-Vec2::X, 1e-5));
+    }
+}
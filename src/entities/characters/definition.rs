@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{loading::EnemyAssets, sprites::AnimatedCharacterType};
+
+use super::brain::{validate_brain, BrainDefinition};
+use super::enemy::EnemyType;
+
+// ------
+// Assets
+// ------
+
+/// Stats, looks and behaviour of one enemy type.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct EnemyDefinition {
+    pub health: u16,
+    /// Fraction of the damage of every hit the enemy ignores.
+    pub resistance: f32,
+    /// Health the player loses when touching the enemy.
+    pub contact_damage: u16,
+    /// Points for a kill, before the combo multiplier.
+    pub score: u32,
+    /// Experience in the gem dropped on death.
+    pub xp: u32,
+    /// Every enemy gets one of these at random.
+    pub sprite_sheets: Vec<EnemySpriteSheet>,
+    /// Half extents of the collider.
+    pub collider: Vec2,
+    pub brain: BrainDefinition,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnemySpriteSheet {
+    pub atlas: Handle<TextureAtlas>,
+    pub animation: AnimatedCharacterType,
+}
+
+impl EnemyDefinition {
+    /// Damage taken from a hit of `damage`.
+    pub fn damage_taken(&self, damage: u16) -> u16 {
+        (damage as f32 * (1.0 - self.resistance)) as u16
+    }
+}
+
+/// Definitions of every enemy type.
+#[derive(SystemParam)]
+pub struct EnemyDefinitions<'w> {
+    enemy_assets: Res<'w, EnemyAssets>,
+    definitions: Res<'w, Assets<EnemyDefinition>>,
+}
+
+impl<'w> EnemyDefinitions<'w> {
+    pub fn get(&self, enemy_type: EnemyType) -> &EnemyDefinition {
+        self.definitions
+            .get(self.enemy_assets.definition(enemy_type))
+            .expect("enemy definitions are loaded before the game starts")
+    }
+}
+
+// ------
+// Loader
+// ------
+
+#[derive(Deserialize)]
+struct EnemyDefinitionFile {
+    health: u16,
+    #[serde(default)]
+    resistance: f32,
+    contact_damage: u16,
+    score: u32,
+    xp: u32,
+    sprite_sheets: Vec<SpriteSheetDefinition>,
+    collider: Vec2,
+    brain: BrainDefinition,
+}
+
+#[derive(Deserialize)]
+struct SpriteSheetDefinition {
+    path: String,
+    columns: usize,
+    rows: usize,
+    frame_size: Vec2,
+    /// Animations played with the sheet, a still image by default.
+    #[serde(default = "not_animated")]
+    animation: AnimatedCharacterType,
+}
+
+fn not_animated() -> AnimatedCharacterType {
+    AnimatedCharacterType::NotAnimated
+}
+
+#[derive(Debug, Error)]
+pub enum EnemyDefinitionLoaderError {
+    #[error("could not read enemy definition {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not parse enemy definition {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    #[error("{}: {reason}", path.display())]
+    Invalid { path: PathBuf, reason: String },
+}
+
+#[derive(Default)]
+pub struct EnemyDefinitionLoader;
+
+impl AssetLoader for EnemyDefinitionLoader {
+    type Asset = EnemyDefinition;
+    type Settings = ();
+    type Error = EnemyDefinitionLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<EnemyDefinition, EnemyDefinitionLoaderError>> {
+        Box::pin(async move {
+            let path = load_context.path().to_path_buf();
+
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await.map_err(|source| {
+                EnemyDefinitionLoaderError::Io {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+
+            let file: EnemyDefinitionFile = ron::de::from_bytes(&bytes).map_err(|source| {
+                EnemyDefinitionLoaderError::Parse {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+
+            if let Err(reason) = validate_enemy_definition(&file) {
+                return Err(EnemyDefinitionLoaderError::Invalid {
+                    path,
+                    reason: reason.to_string(),
+                });
+            }
+
+            let sprite_sheets = file
+                .sprite_sheets
+                .iter()
+                .enumerate()
+                .map(|(index, sheet)| {
+                    let atlas = TextureAtlas::from_grid(
+                        load_context.load(&sheet.path),
+                        sheet.frame_size,
+                        sheet.columns,
+                        sheet.rows,
+                        None,
+                        None,
+                    );
+
+                    EnemySpriteSheet {
+                        atlas: load_context.add_labeled_asset(format!("sheet{}", index), atlas),
+                        animation: sheet.animation,
+                    }
+                })
+                .collect();
+
+            Ok(EnemyDefinition {
+                health: file.health,
+                resistance: file.resistance,
+                contact_damage: file.contact_damage,
+                score: file.score,
+                xp: file.xp,
+                sprite_sheets,
+                collider: file.collider,
+                brain: file.brain,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemy.ron"]
+    }
+}
+
+fn validate_enemy_definition(file: &EnemyDefinitionFile) -> Result<(), &'static str> {
+    if file.health == 0 {
+        return Err("health must be at least 1");
+    }
+    if !(0.0..=1.0).contains(&file.resistance) {
+        return Err("resistance must be between 0.0 and 1.0");
+    }
+    if file.sprite_sheets.is_empty() {
+        return Err("enemy has no sprite sheets");
+    }
+    if file.sprite_sheets.iter().any(|sheet| {
+        sheet.columns == 0 || sheet.rows == 0 || !sheet.frame_size.cmpgt(Vec2::ZERO).all()
+    }) {
+        return Err("sprite sheets need at least one frame of a positive size");
+    }
+    if !file.collider.cmpgt(Vec2::ZERO).all() {
+        return Err("collider must have a positive size");
+    }
+
+    validate_brain(&file.brain)
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct EnemyDefinitionPlugin;
+
+impl Plugin for EnemyDefinitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<EnemyDefinition>()
+            .init_asset_loader::<EnemyDefinitionLoader>();
+    }
+}
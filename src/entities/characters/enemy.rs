@@ -3,33 +3,38 @@ use bevy_ecs_ldtk::prelude::*;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::Velocity;
 use bevy_rapier2d::prelude::*;
-use pecs::prelude::*;
 use rand::seq::SliceRandom;
 use rand::Rng;
-use std::time::Duration;
+use serde::Deserialize;
 
 use crate::{
-    gameplay::scoring::Score, loading::load_texture_atlas, physics::ColliderBundle, sprites::*,
-    utils::CloneEntity, AudioAssets, GameState,
+    gameplay::rng::{GameRng, GameRngSet},
+    gameplay::scoring::{Combo, Score},
+    gameplay::spawn_pattern::{SpawnArea, SpawnPattern},
+    physics::ColliderBundle,
+    sprites::*,
+    utils::CloneEntity,
+    AudioAssets, GameState,
 };
 
+use crate::entities::health::{
+    animate_deaths, apply_health_changes, DamageEvent, Dead, DeathAnimation, DeathEvent, Health,
+};
+use crate::entities::level_objects::spawn_point::{spawn_point_positions, SpawnPoint};
 use crate::entities::player::Player;
+use crate::entities::status_effects::{
+    ApplyStatusEffectEvent, StatusEffectHit, StatusEffectKind, StatusEffects,
+};
 use crate::entities::text_indicator::SpawnTextIndicatorEvent;
 
-// ----------
-// Components
-// ----------
-
-#[derive(Component, Clone, Default, Reflect)]
-pub struct DirectionUpdateTime {
-    pub timer: Timer,
-}
+use super::boss::Boss;
+use super::definition::EnemyDefinitions;
 
 // --------
 // Entities
 // --------
 
-#[derive(Clone, Copy, PartialEq, Debug, Default, Component, Reflect)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Component, Reflect, Deserialize)]
 pub enum EnemyType {
     #[default]
     Mierda,
@@ -42,140 +47,39 @@ pub enum EnemyType {
 pub struct Enemy {
     pub enemy_type: EnemyType,
     pub move_direction: Vec2,
-    pub health: u16,
-    pub hit_at: Option<Timer>,
     pub is_dummy: bool,
-    pub marked_for_despawn: bool,
 }
 
 #[derive(Default, Bundle, Clone)]
 pub struct EnemyBundle {
     pub spritesheet_bundle: SpriteSheetBundle,
-    pub character_animation: CharacterAnimation,
-    pub animation_timer: AnimationTimer,
     pub enemy: Enemy,
-    pub collider_bundle: ColliderBundle,
-    pub active_events: ActiveEvents,
-    pub direction_update_time: DirectionUpdateTime,
-    pub animated_character_sprite: AnimatedCharacterSprite,
 }
 
 // ----
 // LDTK
 // ----
 
+/// Looks, collider and health are filled in from the enemy's definition once it's spawned,
+/// see [`apply_enemy_definitions`].
 pub fn create_enemy_bundle(
-    asset_server: &AssetServer,
-    texture_atlasses: &mut Assets<TextureAtlas>,
     is_dummy: bool,
     enemy_type: EnemyType,
+    rng: &mut impl Rng,
 ) -> EnemyBundle {
-    let rotation_constraints = LockedAxes::ROTATION_LOCKED;
-
-    let collider_bundle = ColliderBundle {
-        collider: Collider::cuboid(8., 26.),
-        rigid_body: RigidBody::Dynamic,
-        friction: Friction {
-            coefficient: 0.0,
-            combine_rule: CoefficientCombineRule::Min,
-        },
-        rotation_constraints,
-        ..Default::default()
-    };
-
-    let (atlas_handle, spritesheet_type) = match enemy_type {
-        EnemyType::Mierda => (
-            load_texture_atlas(
-                MIERDA_ASSET_SHEET.to_string(),
-                asset_server,
-                5,
-                1,
-                None,
-                Vec2::ONE * 16.,
-                texture_atlasses,
-            ),
-            AnimatedCharacterType::NotAnimated,
-        ),
-        EnemyType::Pendejo => {
-            let (spritesheet_path, spritesheet_type) = PENDEJO_SPRITE_SHEETS
-                .choose(&mut rand::thread_rng())
-                .unwrap();
-
-            (
-                load_texture_atlas(
-                    spritesheet_path.to_string(),
-                    asset_server,
-                    SHEET_1_COLUMNS,
-                    SHEET_1_ROWS,
-                    None,
-                    Vec2::ONE * 64.,
-                    texture_atlasses,
-                ),
-                *spritesheet_type,
-            )
-        }
-        EnemyType::Psychiatrist1 => (
-            load_texture_atlas(
-                PSYCHIATRIST_1_ASSET_SHEET.to_string(),
-                asset_server,
-                1,
-                1,
-                None,
-                128. * Vec2::ONE,
-                texture_atlasses,
-            ),
-            AnimatedCharacterType::NotAnimated,
-        ),
-        EnemyType::Psychiatrist2 => (
-            load_texture_atlas(
-                PSYCHIATRIST_2_ASSET_SHEET.to_string(),
-                asset_server,
-                1,
-                1,
-                None,
-                128. * Vec2::ONE,
-                texture_atlasses,
-            ),
-            AnimatedCharacterType::NotAnimated,
-        ),
-    };
-
-    let sprite_bundle = SpriteSheetBundle {
-        texture_atlas: atlas_handle,
-        sprite: TextureAtlasSprite::new(0),
-        ..default()
-    };
-
     let enemy = Enemy {
-        health: 100,
         enemy_type,
         move_direction: Vec2 {
-            x: rand::random::<f32>() * 2.0 - 1.0,
-            y: rand::random::<f32>() * 2.0 - 1.0,
+            x: rng.gen::<f32>() * 2.0 - 1.0,
+            y: rng.gen::<f32>() * 2.0 - 1.0,
         }
         .normalize(),
-        hit_at: None,
         is_dummy,
-        marked_for_despawn: false,
     };
 
     EnemyBundle {
-        character_animation: CharacterAnimation {
-            state: AnimationState::default(),
-            direction: AnimationDirection::Right,
-            animation_type: AnimationType::Walk,
-        },
-        animation_timer: AnimationTimer(Timer::from_seconds(0.2, TimerMode::Repeating)),
-        spritesheet_bundle: sprite_bundle,
-        collider_bundle,
-        active_events: ActiveEvents::COLLISION_EVENTS,
+        spritesheet_bundle: SpriteSheetBundle::default(),
         enemy,
-        direction_update_time: DirectionUpdateTime {
-            timer: Timer::new(Duration::from_secs(5), TimerMode::Once),
-        },
-        animated_character_sprite: AnimatedCharacterSprite {
-            animated_character_type: spritesheet_type,
-        },
     }
 }
 
@@ -187,12 +91,17 @@ pub fn create_enemy_bundle(
 pub struct EnemyHitEvent {
     pub entity: Entity,
     pub damage: u16,
+    /// Applied on top of the short stun of every hit.
+    pub effect: Option<StatusEffectHit>,
 }
 
 #[derive(Event, Clone)]
 pub struct SpawnEnemyEvent {
     pub count: u32,
     pub enemy_type: EnemyType,
+    pub pattern: SpawnPattern,
+    /// Spawns the enemies as [`Boss`]es.
+    pub boss: bool,
 }
 
 // --------------
@@ -208,104 +117,157 @@ pub fn handle_spawn_enemy(
     project_assets: Res<Assets<LdtkProject>>,
     enemies: Query<(Entity, &Parent, &Enemy)>,
     q_player_query: Query<(Entity, &Transform, &Player)>,
+    q_spawn_points: Query<(&Transform, &SpawnPoint)>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     if q_player_query.iter().count() == 0 {
         return;
     }
 
-    let mut rng = rand::thread_rng();
     let player_translation = q_player_query.single().1.translation;
+    let spawn_points = spawn_point_positions(&q_spawn_points);
 
     for ev_spawn in ev_spawn_enemy.read() {
         for (_, level_iid) in levels.iter() {
             let project = project_assets.get(projects.single()).unwrap();
             let level = project.get_raw_level_by_iid(level_iid.get()).unwrap();
-            let max_level_dimension = level.px_wid.max(level.px_hei) as f32;
 
-            if level_selection.is_match(
+            if !level_selection.is_match(
                 &LevelIndices {
                     level: 0,
                     ..default()
                 },
                 level,
             ) {
-                for _i in 0..ev_spawn.count {
-                    for (mierda_entity, enemy_parent, mierda) in enemies.iter() {
-                        if !mierda.is_dummy {
-                            continue;
-                        }
-                        if mierda.enemy_type != ev_spawn.enemy_type {
-                            continue;
-                        }
-
-                        let enemy_parent = enemy_parent.get();
-                        let mut parent = commands.entity(enemy_parent);
-
-                        let mut new_entity: Option<Entity> = None;
-                        parent.with_children(|cm| {
-                            let ne = cm.spawn_empty().id();
-                            new_entity = Some(ne);
-                        });
-
-                        // generate random position
-
-                        let mut offset_position = Vec3::new(0.0, 0.0, 0.);
-                        let mut enemy_position = player_translation + offset_position;
-
-                        while (player_translation - enemy_position).length()
-                            < max_level_dimension / 2.0
-                            || enemy_position.x < 0.0 + 24.0
-                            || enemy_position.x > (level.px_wid as f32) - 24.0
-                            || enemy_position.y < 0.0 + 24.0
-                            || enemy_position.y > (level.px_hei as f32) - 24.0
-                        {
-                            let r = rng.gen_range(0.0..1000.0);
-                            let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-
-                            offset_position =
-                                Vec3::new(r * f32::sin(angle), r * f32::cos(angle), 0.);
-                            enemy_position = player_translation + offset_position;
-                        }
-
-                        let transform =
-                            Transform::from_translation(enemy_position).with_scale(Vec3::ONE * 0.5);
-
-                        let new_entity = new_entity.unwrap();
-                        commands.entity(new_entity).insert(Enemy {
-                            enemy_type: ev_spawn.enemy_type,
-                            is_dummy: false,
-                            health: match ev_spawn.enemy_type {
-                                EnemyType::Mierda => 50,
-                                EnemyType::Pendejo => 100,
-                                EnemyType::Psychiatrist1 => 5000,
-                                EnemyType::Psychiatrist2 => 5000,
-                            },
-                            move_direction: Vec2::ZERO,
-                            hit_at: None,
-                            marked_for_despawn: false,
-                        });
-
-                        commands.add(CloneEntity {
-                            source: mierda_entity,
-                            destination: new_entity,
-                        });
-
-                        commands.entity(new_entity).insert(transform);
-                        break;
-                    }
+                continue;
+            }
+
+            let Some((dummy_entity, dummy_parent, _)) = enemies
+                .iter()
+                .find(|(_, _, e)| e.is_dummy && e.enemy_type == ev_spawn.enemy_type)
+            else {
+                warn!("no dummy {:?} in level to spawn from", ev_spawn.enemy_type);
+                continue;
+            };
+
+            let spawn_area = SpawnArea {
+                level_size: Vec2::new(level.px_wid as f32, level.px_hei as f32),
+                player_position: player_translation.truncate(),
+                safe_distance: level.px_wid.max(level.px_hei) as f32 / 2.0,
+                spawn_points: spawn_points.clone(),
+            };
+
+            for position in
+                spawn_area.positions(&ev_spawn.pattern, ev_spawn.count as usize, &mut *game_rng)
+            {
+                let mut parent = commands.entity(dummy_parent.get());
+
+                let mut new_entity: Option<Entity> = None;
+                parent.with_children(|cm| {
+                    let ne = cm.spawn_empty().id();
+                    new_entity = Some(ne);
+                });
+
+                let transform = Transform::from_translation(position.extend(player_translation.z))
+                    .with_scale(Vec3::ONE * 0.5);
+
+                let new_entity = new_entity.unwrap();
+                commands.entity(new_entity).insert(Enemy {
+                    enemy_type: ev_spawn.enemy_type,
+                    is_dummy: false,
+                    move_direction: Vec2::ZERO,
+                });
+
+                if ev_spawn.boss {
+                    commands.entity(new_entity).insert(Boss::default());
                 }
+
+                commands.add(CloneEntity {
+                    source: dummy_entity,
+                    destination: new_entity,
+                });
+
+                commands.entity(new_entity).insert(transform);
             }
         }
     }
 }
 
+/// Gives enemies placed in LDtk or cloned from a dummy the components of their definition.
+pub fn apply_enemy_definitions(
+    mut commands: Commands,
+    q_enemies: Query<(Entity, &Enemy), Added<Enemy>>,
+    enemy_definitions: EnemyDefinitions,
+    mut game_rng: ResMut<GameRng>,
+) {
+    for (entity, enemy) in q_enemies.iter() {
+        let definition = enemy_definitions.get(enemy.enemy_type);
+        let sprite_sheet = definition
+            .sprite_sheets
+            .choose(&mut *game_rng)
+            .expect("enemy definitions have at least one sprite sheet");
+
+        commands.entity(entity).insert((
+            sprite_sheet.atlas.clone(),
+            TextureAtlasSprite::new(0),
+            AnimatedCharacterSprite {
+                animated_character_type: sprite_sheet.animation,
+            },
+            CharacterAnimation {
+                state: AnimationState::default(),
+                direction: AnimationDirection::Right,
+                animation_type: AnimationType::Walk,
+            },
+            AnimationTimer(Timer::from_seconds(0.2, TimerMode::Repeating)),
+        ));
+
+        if enemy.is_dummy {
+            continue;
+        }
+
+        commands.entity(entity).insert((
+            Health::new(definition.health),
+            ColliderBundle {
+                collider: Collider::cuboid(definition.collider.x, definition.collider.y),
+                rigid_body: RigidBody::Dynamic,
+                friction: Friction {
+                    coefficient: 0.0,
+                    combine_rule: CoefficientCombineRule::Min,
+                },
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                ..Default::default()
+            },
+            ActiveEvents::COLLISION_EVENTS,
+            // clones copy the hidden visibility of their dummy
+            Visibility::Visible,
+        ));
+    }
+}
+
+/// Every hit stops the enemy for a moment, its knockback isn't steered away.
+pub const HIT_STUN: StatusEffectHit = StatusEffectHit {
+    kind: StatusEffectKind::Stun,
+    duration: 0.2,
+};
+
 pub fn handle_enemy_hit(
     mut commands: Commands,
     q_player: Query<(&Transform, &Player)>,
-    mut enemies: Query<(Entity, &Transform, &mut Velocity, &mut Enemy)>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &Enemy,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead>,
+    >,
     mut ev_enemy_hit: EventReader<EnemyHitEvent>,
+    mut ew_damage: EventWriter<DamageEvent>,
+    mut ew_status_effect: EventWriter<ApplyStatusEffectEvent>,
     mut ev_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
-
+    enemy_definitions: EnemyDefinitions,
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
 ) {
@@ -319,23 +281,38 @@ pub fn handle_enemy_hit(
         for (player_transform, _) in q_player.iter() {
             let player_position = player_transform.translation;
 
-            let (enemy_entity, mierda_transform, mut enemy_velocity, mut enemy) =
-                enemies.get_mut(event.entity).unwrap();
+            let Ok((enemy_entity, mierda_transform, mut enemy_velocity, enemy, status_effects)) =
+                enemies.get_mut(event.entity)
+            else {
+                continue;
+            };
+            if status_effects.is_some_and(|effects| effects.is_shielded()) {
+                continue;
+            }
             let enemy_position = mierda_transform.translation;
             let vector_attack = (enemy_position - player_position).normalize();
             enemy_velocity.linvel.x += vector_attack.x * 500.;
             enemy_velocity.linvel.y += vector_attack.y * 500.;
 
-            let damage = match enemy.enemy_type {
-                EnemyType::Mierda => (1.0 * event.damage as f32) as u16,
-                EnemyType::Pendejo => (0.5 * event.damage as f32) as u16,
-                EnemyType::Psychiatrist1 => (1.0 * event.damage as f32) as u16,
-                EnemyType::Psychiatrist2 => (1.0 * event.damage as f32) as u16,
-            };
+            let damage = enemy_definitions
+                .get(enemy.enemy_type)
+                .damage_taken(event.damage);
 
-            let timer = Timer::new(std::time::Duration::from_millis(200), TimerMode::Once);
-            enemy.hit_at = Some(timer.clone());
-            enemy.health -= u16::min(damage, enemy.health);
+            let timer = Timer::from_seconds(HIT_STUN.duration, TimerMode::Once);
+            ew_status_effect.send(ApplyStatusEffectEvent {
+                entity: enemy_entity,
+                effect: HIT_STUN,
+            });
+            if let Some(effect) = event.effect {
+                ew_status_effect.send(ApplyStatusEffectEvent {
+                    entity: enemy_entity,
+                    effect,
+                });
+            }
+            ew_damage.send(DamageEvent {
+                entity: enemy_entity,
+                amount: damage,
+            });
 
             if !hit_sound_played {
                 audio.play(audio_assets.hit.clone()).with_volume(0.05);
@@ -354,39 +331,52 @@ pub fn handle_enemy_hit(
     }
 }
 
-pub fn despawn_dead_enemies(
+/// Seconds an enemy's death animation plays before it's despawned.
+const ENEMY_DEATH_SECS: f32 = 0.3;
+
+pub fn event_enemy_death(
     mut commands: Commands,
-    mut enemies: Query<(Entity, &Transform, &mut Velocity, &mut Enemy)>,
+    mut er_death: EventReader<DeathEvent>,
+    q_enemies: Query<&Enemy>,
+    q_player: Query<Entity, With<Player>>,
     mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut ev_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+    enemy_definitions: EnemyDefinitions,
 ) {
-    for (e, _, _, mut enemy) in enemies.iter_mut() {
-        if enemy.health != 0 {
+    for ev in er_death.read() {
+        let Ok(enemy) = q_enemies.get(ev.entity) else {
             continue;
-        }
+        };
 
-        if enemy.marked_for_despawn {
-            continue;
-        }
+        let multiplier = combo.multiplier();
+        score.score += combo.add_kill(enemy_definitions.get(enemy.enemy_type).score);
 
-        enemy.marked_for_despawn = true;
-        score.score += match enemy.enemy_type {
-            EnemyType::Mierda => 100,
-            EnemyType::Psychiatrist1 => 5000,
-            EnemyType::Psychiatrist2 => 5000,
-            EnemyType::Pendejo => 50,
-        };
+        // only announced when it steps up, crowds die faster than the text can be read
+        if combo.multiplier() > multiplier {
+            if let Ok(player_entity) = q_player.get_single() {
+                ev_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+                    text: format!("COMBO {} x{}", combo.kills, combo.multiplier()),
+                    entity: player_entity,
+                });
+            }
+        }
 
         commands
-            .promise(|| (e))
-            .then(asyn!(state => {
-                state.asyn().timeout(0.3)
-            }))
-            .then(asyn!(state, mut commands: Commands => {
-                if commands.get_entity(state.value).is_none() {
-                    return;
-                }
-                commands.entity(state.value).despawn_recursive();
-            }));
+            .entity(ev.entity)
+            .remove::<FlashingTimer>()
+            .insert(DeathAnimation::new(ENEMY_DEATH_SECS));
+    }
+}
+
+pub fn despawn_dead_enemies(
+    mut commands: Commands,
+    q_enemies: Query<(Entity, &DeathAnimation), With<Enemy>>,
+) {
+    for (entity, animation) in q_enemies.iter() {
+        if animation.0.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
     }
 }
 
@@ -408,9 +398,11 @@ impl Plugin for EnemyPlugin {
                 (
                     // Events
                     handle_enemy_hit,
-                    handle_spawn_enemy,
+                    handle_spawn_enemy.in_set(GameRngSet::SpawnEnemies),
+                    apply_enemy_definitions.in_set(GameRngSet::FixEntities),
+                    event_enemy_death.after(apply_health_changes),
                     // Rest
-                    despawn_dead_enemies,
+                    despawn_dead_enemies.after(animate_deaths),
                 )
                     .run_if(in_state(GameState::GamePlay)),
             );
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+pub mod boss;
+pub mod brain;
+pub mod definition;
 pub mod enemy;
 pub mod mierda;
 pub mod pendejo;
@@ -16,6 +19,9 @@ impl Plugin for CharactersPlugin {
         }
 
         app.add_plugins((
+            boss::BossPlugin,
+            brain::BrainPlugin,
+            definition::EnemyDefinitionPlugin,
             enemy::EnemyPlugin,
             mierda::MierdaPlugin,
             pendejo::PendejoPlugin,
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+
+// ----------
+// Components
+// ----------
+
+/// Health of the player or an enemy, only changed through [`DamageEvent`] and [`HealEvent`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
+pub struct Health {
+    pub current: u16,
+    pub max: u16,
+}
+
+impl Health {
+    pub fn new(max: u16) -> Self {
+        Health { current: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.current as f32 / self.max.max(1) as f32
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+}
+
+/// Added once an entity's health runs out, [`DeathEvent`] is sent at the same time.
+#[derive(Component, Debug)]
+pub struct Dead;
+
+/// Fades out the sprite of a dead entity. What happens once the timer finishes is up to
+/// whoever handles the [`DeathEvent`].
+#[derive(Component, Debug)]
+pub struct DeathAnimation(pub Timer);
+
+impl DeathAnimation {
+    pub fn new(seconds: f32) -> Self {
+        DeathAnimation(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+// ------
+// Events
+// ------
+
+#[derive(Event, Clone)]
+pub struct DamageEvent {
+    pub entity: Entity,
+    pub amount: u16,
+}
+
+#[derive(Event, Clone)]
+pub struct HealEvent {
+    pub entity: Entity,
+    pub amount: u16,
+}
+
+/// Sent exactly once per entity, when its health drops to 0.
+#[derive(Event, Clone)]
+pub struct DeathEvent {
+    pub entity: Entity,
+}
+
+// -------
+// Systems
+// -------
+
+pub fn apply_health_changes(
+    mut commands: Commands,
+    mut er_heal: EventReader<HealEvent>,
+    mut er_damage: EventReader<DamageEvent>,
+    mut q_health: Query<&mut Health, Without<Dead>>,
+    mut ew_death: EventWriter<DeathEvent>,
+) {
+    for ev in er_heal.read() {
+        if let Ok(mut health) = q_health.get_mut(ev.entity) {
+            health.current = health.current.saturating_add(ev.amount).min(health.max);
+        }
+    }
+
+    for ev in er_damage.read() {
+        let Ok(mut health) = q_health.get_mut(ev.entity) else {
+            continue;
+        };
+        // `Dead` is only inserted at the end of the frame
+        if health.is_dead() {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(ev.amount);
+
+        if health.is_dead() {
+            commands.entity(ev.entity).insert(Dead);
+            ew_death.send(DeathEvent { entity: ev.entity });
+        }
+    }
+}
+
+pub fn animate_deaths(
+    time: Res<Time>,
+    mut q_dying: Query<(&mut DeathAnimation, &mut TextureAtlasSprite)>,
+) {
+    for (mut animation, mut sprite) in q_dying.iter_mut() {
+        let fade = 1.0 - animation.0.tick(time.delta()).percent();
+        sprite.color = Color::rgba(1.0, fade, fade, fade);
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<HealEvent>()
+            .add_event::<DeathEvent>()
+            .add_systems(
+                Update,
+                (apply_health_changes, animate_deaths).run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
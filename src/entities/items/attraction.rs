@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{
+    entities::{health::Dead, player::Player},
+    gameplay::progression::PlayerStats,
+    GameState,
+};
+
+/// Pixels per second an attracted pickup flies to the player.
+const ATTRACT_SPEED: f32 = 250.0;
+
+// ----------
+// Components
+// ----------
+
+/// Items and XP gems flying to the player once it came within its pickup radius.
+#[derive(Component, Clone, Default, Debug)]
+pub struct Attractable {
+    /// Set once the player came within its pickup radius, it follows the player from then on.
+    pub attracted: bool,
+}
+
+// -------
+// Systems
+// -------
+
+fn attract_pickups(
+    time: Res<Time>,
+    stats: Res<PlayerStats>,
+    q_player: Query<&Transform, (With<Player>, Without<Dead>)>,
+    mut q_pickups: Query<(&mut Transform, &mut Attractable), Without<Player>>,
+) {
+    let Ok(player_transform) = q_player.get_single() else {
+        return;
+    };
+    // pickups and the player are children of the same entity layer
+    let player_position = player_transform.translation.truncate();
+
+    for (mut transform, mut attractable) in q_pickups.iter_mut() {
+        let offset = player_position - transform.translation.truncate();
+        let distance = offset.length();
+
+        attractable.attracted |= distance <= stats.pickup_radius;
+        if attractable.attracted && distance > 0.0 {
+            let step = (ATTRACT_SPEED * time.delta_seconds()).min(distance);
+            transform.translation += (offset / distance * step).extend(0.0);
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct AttractionPlugin;
+
+impl Plugin for AttractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            attract_pickups.run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
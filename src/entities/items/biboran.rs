@@ -22,8 +22,15 @@ use bevy_scene_hook::{HookedSceneBundle, SceneHook};
 use std::f32::consts::PI;
 
 use crate::{
-    entities::characters::enemy::Enemy, physics::ColliderBundle, sprites::FlashingTimer,
-    AudioAssets, GameState,
+    entities::{
+        characters::enemy::{Enemy, HIT_STUN},
+        health::DamageEvent,
+        status_effects::ApplyStatusEffectEvent,
+    },
+    gameplay::run::{reset_resource, EndRun, StartRun, StartRunSet},
+    physics::ColliderBundle,
+    sprites::FlashingTimer,
+    AudioAssets,
 };
 
 use crate::entities::{
@@ -32,6 +39,7 @@ use crate::entities::{
     text_indicator::SpawnTextIndicatorEvent,
 };
 
+use super::definition::ItemEffect;
 use super::item::create_item_bundle;
 
 // ----------
@@ -100,9 +108,7 @@ pub struct BiboranPrayer(Handle<AudioInstance>);
 
 #[allow(clippy::single_match)]
 pub fn event_on_biboran_step_over(
-    mut commands: Commands,
     mut er_item_step_over: EventReader<ItemStepOverEvent>,
-    mut q_items: Query<(Entity, &Item)>,
     mut q_player: Query<(Entity, &mut Player)>,
     mut q_biboran_animations: Query<(&mut Visibility, &BiboranSprite)>, // mut q_ui_healthbar: Query<(Entity, &mut Style, &ui::UIPlayerHealth)>,
     mut biboran_timer: ResMut<BiboranTimer>,
@@ -113,12 +119,13 @@ pub fn event_on_biboran_step_over(
     mut audio_instances: ResMut<Assets<AudioInstance>>,
 ) {
     for e in er_item_step_over.read() {
-        if e.item_type != ItemType::Biboran {
+        let definition = e.item_type.definition();
+        if definition.effect != ItemEffect::Prayer {
             continue;
         }
 
         for (_, mut _player) in q_player.iter_mut() {
-            biboran_timer.0 = Timer::new(Duration::from_secs(14), TimerMode::Once);
+            biboran_timer.0 = Timer::from_seconds(definition.duration, TimerMode::Once);
             biboran_effect_timer.0 = Timer::new(Duration::from_secs(1), TimerMode::Repeating);
 
             for (mut v, _) in q_biboran_animations.iter_mut() {
@@ -139,16 +146,6 @@ pub fn event_on_biboran_step_over(
         for (mut player, _) in &mut players {
             player.play(animations.0.clone_weak()).repeat();
         }
-
-        for (e_biboran, item) in q_items.iter_mut() {
-            if e_biboran != e.entity {
-                continue;
-            }
-            if item.item_type != ItemType::Biboran {
-                continue;
-            }
-            commands.entity(e_biboran).despawn_recursive();
-        }
     }
 }
 
@@ -263,7 +260,9 @@ fn biboran_holy_effect(
     time: Res<Time>,
     audio: Res<BiboranPrayer>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
-    mut enemies: Query<(Entity, &mut Enemy)>,
+    enemies: Query<Entity, With<Enemy>>,
+    mut ew_damage: EventWriter<DamageEvent>,
+    mut ew_status_effect: EventWriter<ApplyStatusEffectEvent>,
 ) {
     biboran_timer.0.tick(time.delta());
     biboran_effect_timer.0.tick(time.delta());
@@ -277,12 +276,18 @@ fn biboran_holy_effect(
     }
 
     if !biboran_timer.0.finished() && biboran_effect_timer.0.finished() {
-        for (mierda_entity, mut mierda) in enemies.iter_mut() {
+        for mierda_entity in enemies.iter() {
             let damage = 5;
 
-            let timer = Timer::new(std::time::Duration::from_millis(200), TimerMode::Once);
-            mierda.hit_at = Some(timer.clone());
-            mierda.health -= u16::min(damage, mierda.health);
+            let timer = Timer::from_seconds(HIT_STUN.duration, TimerMode::Once);
+            ew_status_effect.send(ApplyStatusEffectEvent {
+                entity: mierda_entity,
+                effect: HIT_STUN,
+            });
+            ew_damage.send(DamageEvent {
+                entity: mierda_entity,
+                amount: damage,
+            });
 
             commands.entity(mierda_entity).insert(FlashingTimer {
                 timer: timer.clone(),
@@ -294,12 +299,18 @@ fn biboran_holy_effect(
             });
         }
 
-        for (enemy_entity, mut enemy) in enemies.iter_mut() {
+        for enemy_entity in enemies.iter() {
             let damage = 5;
 
-            let timer = Timer::new(std::time::Duration::from_millis(200), TimerMode::Once);
-            enemy.hit_at = Some(timer.clone());
-            enemy.health -= u16::min(damage, enemy.health);
+            let timer = Timer::from_seconds(HIT_STUN.duration, TimerMode::Once);
+            ew_status_effect.send(ApplyStatusEffectEvent {
+                entity: enemy_entity,
+                effect: HIT_STUN,
+            });
+            ew_damage.send(DamageEvent {
+                entity: enemy_entity,
+                amount: damage,
+            });
 
             commands.entity(enemy_entity).insert(FlashingTimer {
                 timer: timer.clone(),
@@ -363,6 +374,21 @@ fn ineject_biboran_render_sprite(
 // Audio
 // -----
 
+#[allow(clippy::single_match)]
+fn stop_biboran_prayer(
+    audio: Res<BiboranPrayer>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    if let Some(instance) = audio_instances.get_mut(&audio.0) {
+        match instance.state() {
+            PlaybackState::Playing { .. } | PlaybackState::Paused { .. } => {
+                instance.stop(AudioTween::default());
+            }
+            _ => {}
+        }
+    }
+}
+
 fn setup_biboran_prayer(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
     let handle = audio
         .play(audio_assets.biboran.clone())
@@ -385,7 +411,16 @@ impl Plugin for BiboranPlugin {
             .init_resource::<BiboranTimer>()
             .init_resource::<BiboranEffectTimer>()
             .init_resource::<BiboranPrayer>()
-            .add_systems(OnEnter(GameState::GamePlay), setup_biboran_prayer)
+            .add_systems(
+                StartRun,
+                (
+                    reset_resource::<BiboranTimer>,
+                    reset_resource::<BiboranEffectTimer>,
+                )
+                    .in_set(StartRunSet::Reset),
+            )
+            .add_systems(StartRun, setup_biboran_prayer.in_set(StartRunSet::Setup))
+            .add_systems(EndRun, stop_biboran_prayer)
             .add_systems(Startup, setup_biboran_scene)
             .add_systems(Update, biboran_holy_effect)
             // Event Handlers
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    entities::status_effects::StatusEffectKind,
+    gameplay::rng::GameRng,
+    sprites::{
+        BIBORAN_ASSET_SHEET, BOMB_ASSET_SHEET, PIZZA_ASSET_SHEET, SHIELD_ASSET_SHEET,
+        SPEED_BOOST_ASSET_SHEET,
+    },
+};
+
+use super::item::ItemType;
+
+// -----------
+// Definitions
+// -----------
+
+impl ItemType {
+    pub const ALL: [ItemType; 5] = [
+        ItemType::Pizza,
+        ItemType::Biboran,
+        ItemType::SpeedBoost,
+        ItemType::Shield,
+        ItemType::Bomb,
+    ];
+
+    pub fn definition(&self) -> &'static ItemDefinition {
+        match self {
+            ItemType::Pizza => &PIZZA,
+            ItemType::Biboran => &BIBORAN,
+            ItemType::SpeedBoost => &SPEED_BOOST,
+            ItemType::Shield => &SHIELD,
+            ItemType::Bomb => &BOMB,
+        }
+    }
+
+    /// An item picked at random, weighted by [`ItemDefinition::rarity`].
+    pub fn roll(game_rng: &mut GameRng) -> ItemType {
+        *ItemType::ALL
+            .choose_weighted(game_rng, |item_type| item_type.definition().rarity)
+            .expect("items have a positive rarity")
+    }
+}
+
+/// What happens when the player picks up an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemEffect {
+    Heal(u16),
+    /// Shows the biboran and damages every enemy while the prayer plays.
+    Prayer,
+    /// Puts a status effect on the player.
+    Status(StatusEffectKind),
+    /// Hits every enemy on screen for this much damage.
+    Bomb(u16),
+}
+
+/// Looks and behaviour of an [`ItemType`].
+#[derive(Debug, Clone)]
+pub struct ItemDefinition {
+    pub name: &'static str,
+    pub sprite: &'static str,
+    /// Size of a frame of the sprite sheet, the first one is shown.
+    pub sprite_size: Vec2,
+    pub tint: Color,
+    /// Half extents of the collider.
+    pub collider: Vec2,
+    /// Played on pickup.
+    pub sound: Option<&'static str>,
+    /// Weight of the item when a random one is spawned, higher is more common.
+    pub rarity: u32,
+    /// Seconds a spawned item lies around before it's gone, `None` keeps it.
+    pub lifetime: Option<f32>,
+    pub effect: ItemEffect,
+    /// Seconds the effect lasts, for effects that wear off.
+    pub duration: f32,
+}
+
+const PIZZA: ItemDefinition = ItemDefinition {
+    name: "PIZZA",
+    sprite: PIZZA_ASSET_SHEET,
+    sprite_size: Vec2::splat(16.0),
+    tint: Color::WHITE,
+    collider: Vec2::new(8.0, 8.0),
+    sound: None,
+    rarity: 10,
+    lifetime: Some(20.0),
+    effect: ItemEffect::Heal(10),
+    duration: 0.0,
+};
+
+const BIBORAN: ItemDefinition = ItemDefinition {
+    name: "BIBORAN",
+    sprite: BIBORAN_ASSET_SHEET,
+    sprite_size: Vec2::splat(32.0),
+    tint: Color::WHITE,
+    collider: Vec2::new(8.0, 16.0),
+    sound: None,
+    rarity: 2,
+    lifetime: Some(20.0),
+    effect: ItemEffect::Prayer,
+    duration: 14.0,
+};
+
+const SPEED_BOOST: ItemDefinition = ItemDefinition {
+    name: "SPEED BOOST",
+    sprite: SPEED_BOOST_ASSET_SHEET,
+    sprite_size: Vec2::splat(16.0),
+    tint: Color::WHITE,
+    collider: Vec2::new(8.0, 8.0),
+    sound: None,
+    rarity: 5,
+    lifetime: Some(15.0),
+    effect: ItemEffect::Status(StatusEffectKind::Haste),
+    duration: 8.0,
+};
+
+const SHIELD: ItemDefinition = ItemDefinition {
+    name: "SHIELD",
+    sprite: SHIELD_ASSET_SHEET,
+    sprite_size: Vec2::splat(16.0),
+    tint: Color::WHITE,
+    collider: Vec2::new(8.0, 8.0),
+    sound: None,
+    rarity: 3,
+    lifetime: Some(15.0),
+    effect: ItemEffect::Status(StatusEffectKind::Shield),
+    duration: 6.0,
+};
+
+const BOMB: ItemDefinition = ItemDefinition {
+    name: "BOMB",
+    sprite: BOMB_ASSET_SHEET,
+    sprite_size: Vec2::splat(16.0),
+    tint: Color::WHITE,
+    collider: Vec2::new(8.0, 8.0),
+    sound: None,
+    rarity: 1,
+    lifetime: Some(10.0),
+    effect: ItemEffect::Bomb(500),
+    duration: 0.0,
+};
@@ -1,21 +1,33 @@
 use bevy::prelude::*;
-use bevy_ecs_ldtk::prelude::*;
+use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::Rng;
+use serde::Deserialize;
 
 use crate::{
+    entities::health::Dead,
     entities::player::Player,
+    gameplay::rng::{GameRng, GameRngSet},
+    gameplay::spawn_pattern::{PlayerSpawnArea, SpawnAreaParam, SpawnPattern},
     loading::load_texture_atlas,
     physics::ColliderBundle,
-    sprites::{BIBORAN_ASSET_SHEET, PIZZA_ASSET_SHEET},
-    utils::*,
+    GameState,
 };
 
-#[derive(Clone, Copy, PartialEq, Debug, Default, Component, Reflect)]
+use super::attraction::Attractable;
+
+/// Items blink for their last seconds before they are gone.
+pub const ITEM_BLINK_SECS: f32 = 3.0;
+/// Seconds an expiring item is shown or hidden at a time.
+const ITEM_BLINK_INTERVAL: f32 = 0.15;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Component, Reflect, Deserialize)]
 pub enum ItemType {
     #[default]
     Pizza,
     Biboran,
+    SpeedBoost,
+    Shield,
+    Bomb,
 }
 
 #[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
@@ -24,6 +36,10 @@ pub struct Item {
     pub item_type: ItemType,
 }
 
+/// Counts down until a spawned item is gone, it blinks for the last [`ITEM_BLINK_SECS`].
+#[derive(Component, Debug)]
+pub struct ItemLifetime(pub Timer);
+
 #[derive(Clone, Default, Bundle)]
 pub struct ItemBundle {
     pub sprite_bundle: SpriteSheetBundle,
@@ -38,55 +54,35 @@ pub fn create_item_bundle(
     is_dummy: bool,
     item_type: ItemType,
 ) -> ItemBundle {
-    let rotation_constraints = LockedAxes::ROTATION_LOCKED;
-
-    let collider_bundle = match item_type {
-        ItemType::Pizza => ColliderBundle {
-            collider: Collider::cuboid(8., 8.),
-            rigid_body: RigidBody::Dynamic,
-            friction: Friction {
-                coefficient: 20.0,
-                combine_rule: CoefficientCombineRule::Min,
-            },
-            rotation_constraints,
-            ..Default::default()
-        },
-        ItemType::Biboran => ColliderBundle {
-            collider: Collider::cuboid(8., 16.),
-            rigid_body: RigidBody::Dynamic,
-            friction: Friction {
-                coefficient: 20.0,
-                combine_rule: CoefficientCombineRule::Min,
-            },
-            rotation_constraints,
-            ..Default::default()
+    let definition = item_type.definition();
+
+    let collider_bundle = ColliderBundle {
+        collider: Collider::cuboid(definition.collider.x, definition.collider.y),
+        rigid_body: RigidBody::Dynamic,
+        friction: Friction {
+            coefficient: 20.0,
+            combine_rule: CoefficientCombineRule::Min,
         },
+        rotation_constraints: LockedAxes::ROTATION_LOCKED,
+        ..Default::default()
     };
 
-    let atlas_handle = match item_type {
-        ItemType::Pizza => load_texture_atlas(
-            PIZZA_ASSET_SHEET.to_string(),
-            asset_server,
-            1,
-            1,
-            None,
-            Vec2::ONE * 16.,
-            texture_atlasses,
-        ),
-        ItemType::Biboran => load_texture_atlas(
-            BIBORAN_ASSET_SHEET.to_string(),
-            asset_server,
-            1,
-            1,
-            None,
-            Vec2::ONE * 32.,
-            texture_atlasses,
-        ),
-    };
+    let atlas_handle = load_texture_atlas(
+        definition.sprite.to_string(),
+        asset_server,
+        1,
+        1,
+        None,
+        definition.sprite_size,
+        texture_atlasses,
+    );
 
     let sprite_bundle = SpriteSheetBundle {
         texture_atlas: atlas_handle,
-        sprite: TextureAtlasSprite::new(0),
+        sprite: TextureAtlasSprite {
+            color: definition.tint,
+            ..TextureAtlasSprite::new(0)
+        },
         ..default()
     };
 
@@ -105,6 +101,7 @@ pub fn create_item_bundle(
 // Events
 // ------
 
+/// The player picked up the item, sent once per item.
 #[derive(Event, Clone)]
 pub struct ItemStepOverEvent {
     pub entity: Entity,
@@ -114,7 +111,9 @@ pub struct ItemStepOverEvent {
 #[derive(Event, Clone)]
 pub struct SpawnItemEvent {
     pub count: u32,
-    pub item_type: ItemType,
+    /// Rolled for every item by rarity when `None`.
+    pub item_type: Option<ItemType>,
+    pub pattern: SpawnPattern,
 }
 
 // --------------
@@ -124,92 +123,51 @@ pub struct SpawnItemEvent {
 pub fn event_spawn_item(
     mut commands: Commands,
     mut ev_spawn_item: EventReader<SpawnItemEvent>,
-    level_selection: Res<LevelSelection>,
-    levels: Query<(Entity, &LevelIid)>,
-    projects: Query<&Handle<LdtkProject>>,
-    project_assets: Res<Assets<LdtkProject>>,
-    q_items: Query<(Entity, &Parent, &Item)>,
-    q_player_query: Query<(Entity, &Transform, &Player)>,
+    spawn_area: SpawnAreaParam,
+    asset_server: Res<AssetServer>,
+    mut texture_atlasses: ResMut<Assets<TextureAtlas>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    if q_player_query.iter().count() == 0 {
+    let Some(PlayerSpawnArea { layer, z, area }) = spawn_area.get(1.0 / 3.0) else {
         return;
-    }
-
-    let mut rng = rand::thread_rng();
-    let player_translation = q_player_query.single().1.translation;
+    };
 
     for ev_spawn in ev_spawn_item.read() {
-        for (_, level_iid) in levels.iter() {
-            let project = project_assets.get(projects.single()).unwrap();
-            let level = project.get_raw_level_by_iid(level_iid.get()).unwrap();
-            let max_level_dimension = level.px_wid.max(level.px_hei) as f32;
-
-            if level_selection.is_match(
-                &LevelIndices {
-                    level: 0,
-                    ..default()
-                },
-                level,
-            ) {
-                for _i in 0..ev_spawn.count {
-                    for (item_entity, item_parent, item) in q_items.iter() {
-                        if !item.is_dummy {
-                            continue;
-                        }
-
-                        if item.item_type != ev_spawn.item_type {
-                            continue;
-                        }
-
-                        let item_parent = item_parent.get();
-
-                        let mut parent = commands.entity(item_parent);
-
-                        let mut new_entity: Option<Entity> = None;
-                        parent.with_children(|cm| {
-                            let ne = cm.spawn_empty().id();
-                            new_entity = Some(ne);
-                        });
-
-                        // generate random position
-
-                        let mut offset_position = Vec3::new(0.0, 0.0, 0.);
-                        let mut item_position = player_translation + offset_position;
-
-                        while (player_translation - item_position).length()
-                            < max_level_dimension / 3.0
-                            || item_position.x < 0.0 + 24.0
-                            || item_position.x > (level.px_wid as f32) - 24.0
-                            || item_position.y < 0.0 + 24.0
-                            || item_position.y > (level.px_hei as f32) - 24.0
-                        {
-                            let r = rng.gen_range(0.0..1000.0);
-                            let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-
-                            offset_position =
-                                Vec3::new(r * f32::sin(angle), r * f32::cos(angle), 0.);
-                            item_position = player_translation + offset_position;
-                        }
-
-                        let transform =
-                            Transform::from_translation(item_position).with_scale(Vec3::ONE * 0.5);
-
-                        let new_entity = new_entity.unwrap();
-                        commands.entity(new_entity).insert(Item {
-                            is_dummy: false,
-                            item_type: ev_spawn.item_type,
-                        });
-
-                        commands.add(CloneEntity {
-                            source: item_entity,
-                            destination: new_entity,
-                        });
-
-                        commands.entity(new_entity).insert(transform);
-                        break;
-                    }
+        for position in area.positions(&ev_spawn.pattern, ev_spawn.count as usize, &mut *game_rng) {
+            let item_type = ev_spawn
+                .item_type
+                .unwrap_or_else(|| ItemType::roll(&mut game_rng));
+            let definition = item_type.definition();
+
+            let mut bundle =
+                create_item_bundle(&asset_server, &mut texture_atlasses, false, item_type);
+            bundle.sprite_bundle.transform = Transform::from_translation(position.extend(z));
+
+            commands.entity(layer).with_children(|parent| {
+                let mut item =
+                    parent.spawn((bundle, Attractable::default(), Name::new(definition.name)));
+                if let Some(lifetime) = definition.lifetime {
+                    item.insert(ItemLifetime(Timer::from_seconds(lifetime, TimerMode::Once)));
                 }
-            }
+            });
+        }
+    }
+}
+
+/// Plays the pickup sound and removes the item, its effect is up to the handler of its type.
+fn event_pick_up_item(
+    mut commands: Commands,
+    mut er_item_step_over: EventReader<ItemStepOverEvent>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+) {
+    for ev in er_item_step_over.read() {
+        if let Some(sound) = ev.item_type.definition().sound {
+            audio.play(asset_server.load(sound)).with_volume(0.1);
+        }
+
+        if let Some(entity) = commands.get_entity(ev.entity) {
+            entity.despawn_recursive();
         }
     }
 }
@@ -220,33 +178,73 @@ pub fn event_spawn_item(
 
 pub fn handle_player_item_collision(
     mut collision_events: EventReader<CollisionEvent>,
-    mut q_items: Query<(Entity, &Item)>,
-    q_player: Query<(Entity, &mut Player)>,
+    q_items: Query<&Item>,
+    q_player: Query<(), (With<Player>, Without<Dead>)>,
     mut ev_item_step_over: EventWriter<ItemStepOverEvent>,
 ) {
-    for (player_entity, _) in q_player.iter() {
-        for event in collision_events.read() {
-            for (e_item, item) in q_items.iter_mut() {
-                if let CollisionEvent::Started(e1, e2, _) = event {
-                    if e1.index() == e_item.index() && e2.index() == player_entity.index() {
-                        ev_item_step_over.send(ItemStepOverEvent {
-                            entity: e_item,
-                            item_type: item.item_type,
-                        });
-
-                        return;
-                    }
-
-                    if e2.index() == e_item.index() && e1.index() == player_entity.index() {
-                        ev_item_step_over.send(ItemStepOverEvent {
-                            entity: e_item,
-                            item_type: item.item_type,
-                        });
-
-                        return;
-                    }
-                }
-            }
+    // the item is only despawned at the end of the frame
+    let mut picked_up = Vec::new();
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+
+        let (item_entity, other) = match (q_items.contains(*e1), q_items.contains(*e2)) {
+            (true, _) => (*e1, *e2),
+            (_, true) => (*e2, *e1),
+            _ => continue,
+        };
+        if !q_player.contains(other) || picked_up.contains(&item_entity) {
+            continue;
+        }
+
+        let item = q_items.get(item_entity).unwrap();
+        if item.is_dummy {
+            continue;
+        }
+
+        picked_up.push(item_entity);
+        ev_item_step_over.send(ItemStepOverEvent {
+            entity: item_entity,
+            item_type: item.item_type,
+        });
+    }
+}
+
+// -------
+// Systems
+// -------
+
+/// Blinks items out at the end of their lifetime, attracted ones stay until picked up.
+fn expire_items(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_items: Query<(Entity, &mut ItemLifetime, &mut Visibility, &Attractable)>,
+) {
+    for (entity, mut lifetime, mut visibility, attractable) in q_items.iter_mut() {
+        // caught mid-blink, it no longer expires
+        if attractable.attracted {
+            commands.entity(entity).remove::<ItemLifetime>();
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let remaining = lifetime.0.remaining_secs();
+        let shown =
+            remaining > ITEM_BLINK_SECS || (remaining / ITEM_BLINK_INTERVAL) as u32 % 2 == 0;
+        let target = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
         }
     }
 }
@@ -264,6 +262,15 @@ impl Plugin for ItemPlugin {
             .add_event::<SpawnItemEvent>()
             .add_event::<ItemStepOverEvent>()
             // Event Handlers
-            .add_systems(Update, (handle_player_item_collision, event_spawn_item));
+            .add_systems(
+                Update,
+                (
+                    handle_player_item_collision,
+                    event_pick_up_item.after(handle_player_item_collision),
+                    event_spawn_item.in_set(GameRngSet::SpawnItems),
+                    expire_items,
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            );
     }
 }
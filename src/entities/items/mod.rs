@@ -1,8 +1,12 @@
 use bevy::prelude::*;
 
+pub mod attraction;
 pub mod biboran;
+pub mod definition;
 pub mod item;
 pub mod pizza;
+pub mod power_up;
+pub mod xp_gem;
 
 pub struct ItemsPlugin;
 
@@ -14,6 +18,13 @@ impl Plugin for ItemsPlugin {
             wr.register::<item::Item>();
         }
 
-        app.add_plugins((item::ItemPlugin, pizza::PizzaPlugin, biboran::BiboranPlugin));
+        app.add_plugins((
+            attraction::AttractionPlugin,
+            item::ItemPlugin,
+            pizza::PizzaPlugin,
+            biboran::BiboranPlugin,
+            power_up::PowerUpPlugin,
+            xp_gem::XpGemPlugin,
+        ));
     }
 }
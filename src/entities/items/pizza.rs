@@ -1,11 +1,13 @@
-use std::cmp::min;
-
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::{entities::player::Player, physics::ColliderBundle, ui};
+use crate::{
+    entities::{health::HealEvent, player::Player},
+    physics::ColliderBundle,
+};
 
+use super::definition::ItemEffect;
 use super::item::{create_item_bundle, Item, ItemStepOverEvent, ItemType};
 
 #[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
@@ -49,32 +51,19 @@ impl LdtkEntity for PizzaBundle {
 // --------------
 
 pub fn event_on_pizza_step_over(
-    mut commands: Commands,
     mut er_item_step_over: EventReader<ItemStepOverEvent>,
-    mut q_items: Query<(Entity, &Item)>,
-    mut q_player: Query<(Entity, &mut Player)>,
-    mut q_ui_healthbar: Query<(Entity, &mut Style, &ui::UIPlayerHealth)>,
+    q_player: Query<Entity, With<Player>>,
+    mut ew_heal: EventWriter<HealEvent>,
 ) {
     for e in er_item_step_over.read() {
-        if e.item_type != ItemType::Pizza {
+        let ItemEffect::Heal(amount) = e.item_type.definition().effect else {
             continue;
-        }
-        for (_, mut player) in q_player.iter_mut() {
-            player.health = min(player.health + 10, 100);
-
-            for (_, mut style, _) in q_ui_healthbar.iter_mut() {
-                style.width = Val::Percent(player.health as f32);
-            }
-        }
-
-        for (e_item, _) in q_items
-            .iter_mut()
-            .filter(|(_, i)| i.item_type == ItemType::Pizza)
-        {
-            if e_item != e.entity {
-                continue;
-            }
-            commands.entity(e_item).despawn_recursive();
+        };
+        for player in q_player.iter() {
+            ew_heal.send(HealEvent {
+                entity: player,
+                amount,
+            });
         }
     }
 }
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy_magic_light_2d::SpriteCamera;
+
+use crate::{
+    entities::{
+        characters::enemy::{Enemy, EnemyHitEvent},
+        health::Dead,
+        player::Player,
+        status_effects::{ApplyStatusEffectEvent, StatusEffectHit},
+    },
+    GameState,
+};
+
+use super::definition::ItemEffect;
+use super::item::ItemStepOverEvent;
+
+// --------------
+// Event Handlers
+// --------------
+
+/// Items like the speed boost and the shield put their effect on the player.
+fn event_on_status_item_step_over(
+    mut er_item_step_over: EventReader<ItemStepOverEvent>,
+    q_player: Query<Entity, With<Player>>,
+    mut ew_status_effect: EventWriter<ApplyStatusEffectEvent>,
+) {
+    for e in er_item_step_over.read() {
+        let definition = e.item_type.definition();
+        let ItemEffect::Status(kind) = definition.effect else {
+            continue;
+        };
+
+        for player in q_player.iter() {
+            ew_status_effect.send(ApplyStatusEffectEvent {
+                entity: player,
+                effect: StatusEffectHit {
+                    kind,
+                    duration: definition.duration,
+                },
+            });
+        }
+    }
+}
+
+/// Hits every enemy inside the view of the camera.
+fn event_on_bomb_step_over(
+    mut er_item_step_over: EventReader<ItemStepOverEvent>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<SpriteCamera>>,
+    q_enemies: Query<(Entity, &GlobalTransform, &Enemy), Without<Dead>>,
+    mut ew_enemy_hit: EventWriter<EnemyHitEvent>,
+) {
+    for e in er_item_step_over.read() {
+        let ItemEffect::Bomb(damage) = e.item_type.definition().effect else {
+            continue;
+        };
+
+        let Ok((camera, camera_transform)) = q_camera.get_single() else {
+            continue;
+        };
+        let Some(screen) = camera.logical_viewport_rect().and_then(|viewport| {
+            let min = camera.viewport_to_world_2d(camera_transform, viewport.min)?;
+            let max = camera.viewport_to_world_2d(camera_transform, viewport.max)?;
+            Some(Rect::from_corners(min, max))
+        }) else {
+            continue;
+        };
+
+        for (entity, transform, enemy) in q_enemies.iter() {
+            if enemy.is_dummy || !screen.contains(transform.translation().truncate()) {
+                continue;
+            }
+
+            ew_enemy_hit.send(EnemyHitEvent {
+                entity,
+                damage,
+                effect: None,
+            });
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (event_on_status_item_step_over, event_on_bomb_step_over)
+                .run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
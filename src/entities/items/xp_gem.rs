@@ -0,0 +1,108 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::prelude::*;
+
+use crate::{
+    entities::{
+        characters::{definition::EnemyDefinitions, enemy::Enemy},
+        health::{Dead, DeathEvent},
+        player::Player,
+    },
+    gameplay::progression::GainXpEvent,
+    GameState,
+};
+
+use super::attraction::Attractable;
+
+const XP_GEM_SIZE: f32 = 6.0;
+/// Gems this close to the player are collected.
+const XP_GEM_COLLECT_DISTANCE: f32 = 8.0;
+
+// ----------
+// Components
+// ----------
+
+/// Experience dropped by a dead enemy.
+#[derive(Component, Debug)]
+pub struct XpGem {
+    pub xp: u32,
+}
+
+// --------------
+// Event Handlers
+// --------------
+
+fn drop_xp_gems(
+    mut commands: Commands,
+    mut er_death: EventReader<DeathEvent>,
+    q_enemies: Query<(&Transform, &Parent, &Enemy)>,
+    enemy_definitions: EnemyDefinitions,
+) {
+    for ev in er_death.read() {
+        let Ok((transform, parent, enemy)) = q_enemies.get(ev.entity) else {
+            continue;
+        };
+
+        let xp = enemy_definitions.get(enemy.enemy_type).xp;
+        if xp == 0 {
+            continue;
+        }
+
+        commands.entity(parent.get()).with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::CYAN,
+                        custom_size: Some(Vec2::splat(XP_GEM_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(transform.translation)
+                        .with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                    ..default()
+                },
+                XpGem { xp },
+                Attractable::default(),
+                Name::new("xp gem"),
+            ));
+        });
+    }
+}
+
+// -------
+// Systems
+// -------
+
+fn collect_xp_gems(
+    mut commands: Commands,
+    q_player: Query<&Transform, (With<Player>, Without<Dead>)>,
+    q_gems: Query<(Entity, &Transform, &XpGem), Without<Player>>,
+    mut ew_gain_xp: EventWriter<GainXpEvent>,
+) {
+    let Ok(player_transform) = q_player.get_single() else {
+        return;
+    };
+    // gems and the player are children of the same entity layer
+    let player_position = player_transform.translation.truncate();
+
+    for (entity, transform, gem) in q_gems.iter() {
+        if player_position.distance(transform.translation.truncate()) <= XP_GEM_COLLECT_DISTANCE {
+            ew_gain_xp.send(GainXpEvent { amount: gem.xp });
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct XpGemPlugin;
+
+impl Plugin for XpGemPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (drop_xp_gems, collect_xp_gems).run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{entities::player::Player, gameplay::campaign::CampaignProgress, GameState};
+
+const LOCKED_COLOR: Color = Color::rgba(0.6, 0.1, 0.1, 0.6);
+const OPEN_COLOR: Color = Color::rgba(0.1, 0.8, 0.1, 0.6);
+
+/// Door to the next campaign level, open once the current level is cleared.
+#[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
+pub struct LevelExit;
+
+#[derive(Clone, Default, Bundle)]
+pub struct LevelExitBundle {
+    pub sprite_bundle: SpriteBundle,
+    pub level_exit: LevelExit,
+    pub collider: Collider,
+    pub sensor: Sensor,
+    pub active_events: ActiveEvents,
+}
+
+impl LdtkEntity for LevelExitBundle {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        _layer_instance: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        _asset_server: &AssetServer,
+        _texture_atlasses: &mut Assets<TextureAtlas>,
+    ) -> LevelExitBundle {
+        let size = Vec2::new(entity_instance.width as f32, entity_instance.height as f32);
+
+        LevelExitBundle {
+            sprite_bundle: SpriteBundle {
+                sprite: Sprite {
+                    color: LOCKED_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
+            level_exit: LevelExit,
+            collider: Collider::cuboid(size.x / 2., size.y / 2.),
+            sensor: Sensor,
+            active_events: ActiveEvents::COLLISION_EVENTS,
+        }
+    }
+}
+
+// ------
+// Events
+// ------
+
+#[derive(Event, Clone)]
+pub struct LevelExitEvent {
+    pub player: Entity,
+}
+
+// -------
+// Systems
+// -------
+
+fn handle_player_exit_collisions(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_player: Query<Entity, With<Player>>,
+    q_exits: Query<Entity, With<LevelExit>>,
+    mut ew_level_exit: EventWriter<LevelExitEvent>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+
+        for (player, exit) in [(*e1, *e2), (*e2, *e1)] {
+            if q_player.contains(player) && q_exits.contains(exit) {
+                ew_level_exit.send(LevelExitEvent { player });
+            }
+        }
+    }
+}
+
+fn update_exit_sprites(
+    progress: Res<CampaignProgress>,
+    mut q_exits: Query<&mut Sprite, With<LevelExit>>,
+) {
+    let color = match progress.level_cleared {
+        true => OPEN_COLOR,
+        false => LOCKED_COLOR,
+    };
+
+    for mut sprite in q_exits.iter_mut() {
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct ExitPlugin;
+
+impl Plugin for ExitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<LevelExitBundle>("Exit")
+            .add_event::<LevelExitEvent>()
+            .add_systems(
+                Update,
+                (handle_player_exit_collisions, update_exit_sprites)
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
@@ -3,8 +3,8 @@ use bevy_ecs_ldtk::prelude::*;
 use bevy_magic_light_2d::prelude::*;
 
 use crate::{
-    entities::player::Player, ldtk::Wall, load_texture_atlas, sprites::LANTERN_ASSET_SHEET,
-    GameState,
+    entities::player::Player, gameplay::run::RunEntity, ldtk::Wall, load_texture_atlas,
+    sprites::LANTERN_ASSET_SHEET, GameState,
 };
 
 #[derive(Component)]
@@ -103,7 +103,7 @@ pub fn setup_light(
         }
 
         commands
-            .spawn(SpatialBundle::default())
+            .spawn((SpatialBundle::default(), RunEntity))
             .insert(Name::new("lights"))
             .push_children(&lights);
 
@@ -114,6 +114,7 @@ pub fn setup_light(
                 intensity: 0.03,
             },
             Name::new("global_skylight"),
+            RunEntity,
         ));
     }
 
@@ -145,7 +146,7 @@ pub fn setup_light(
         }
 
         commands
-            .spawn(SpatialBundle::default())
+            .spawn((SpatialBundle::default(), RunEntity))
             .insert(Name::new("occluders"))
             .push_children(&occluders);
     }
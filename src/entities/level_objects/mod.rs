@@ -1 +1,3 @@
+pub mod exit;
 pub mod light;
+pub mod spawn_point;
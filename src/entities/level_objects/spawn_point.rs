@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+/// A named position in the level that waves can spawn clusters around.
+#[derive(Clone, PartialEq, Debug, Default, Component, Reflect)]
+pub struct SpawnPoint {
+    pub name: String,
+}
+
+#[derive(Clone, Default, Bundle)]
+pub struct SpawnPointBundle {
+    pub spawn_point: SpawnPoint,
+}
+
+impl LdtkEntity for SpawnPointBundle {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        _layer_instance: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        _asset_server: &AssetServer,
+        _texture_atlasses: &mut Assets<TextureAtlas>,
+    ) -> SpawnPointBundle {
+        let name = entity_instance
+            .get_string_field("name")
+            .expect("expected entity to have non-nullable name string field")
+            .clone();
+
+        SpawnPointBundle {
+            spawn_point: SpawnPoint { name },
+        }
+    }
+}
+
+/// Positions of all spawn points, in the same coordinates as the level entities.
+pub fn spawn_point_positions(
+    q_spawn_points: &Query<(&Transform, &SpawnPoint)>,
+) -> Vec<(String, Vec2)> {
+    q_spawn_points
+        .iter()
+        .map(|(transform, spawn_point)| {
+            (spawn_point.name.clone(), transform.translation.truncate())
+        })
+        .collect()
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct SpawnPointPlugin;
+
+impl Plugin for SpawnPointPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<SpawnPointBundle>("SpawnPoint");
+    }
+}
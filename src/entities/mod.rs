@@ -1,9 +1,11 @@
 use bevy::prelude::*;
 
 pub mod characters;
+pub mod health;
 pub mod items;
 pub mod level_objects;
 pub mod player;
+pub mod status_effects;
 pub mod text_indicator;
 pub mod weapons;
 
@@ -15,15 +17,20 @@ impl Plugin for EntitiesPlugin {
             let registry = app.world.resource_mut::<AppTypeRegistry>();
             let mut wr = registry.write();
             wr.register::<player::Player>();
+            wr.register::<health::Health>();
         }
 
         app.add_plugins((
             characters::CharactersPlugin,
+            health::HealthPlugin,
             player::PlayerPlugin,
+            status_effects::StatusEffectsPlugin,
             items::ItemsPlugin,
             weapons::WeaponsPlugin,
             text_indicator::TextIndicatorPlugin,
             level_objects::light::LightPlugin,
+            level_objects::spawn_point::SpawnPointPlugin,
+            level_objects::exit::ExitPlugin,
         ));
     }
 }
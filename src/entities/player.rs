@@ -6,20 +6,32 @@ use bevy_particle_systems::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    gameover::GameOverEvent, loading::load_texture_atlas,
+    controls::ControlSet,
+    gameover::{GameEndReason, GameOverEvent},
+    gameplay::{run::RunEntity, scoring::Combo},
+    loading::load_texture_atlas,
     physics::ColliderBundle, sprites::*, ui::UIPlayerHealth, AudioAssets, GameState,
 };
 
+use super::characters::definition::EnemyDefinitions;
 use super::characters::enemy::{Enemy, EnemyHitEvent};
+use super::health::{
+    animate_deaths, apply_health_changes, DamageEvent, Dead, DeathAnimation, DeathEvent, Health,
+};
+use super::status_effects::StatusEffects;
+use super::text_indicator::SpawnTextIndicatorEvent;
+use super::weapons::{
+    definition::WeaponKind,
+    inventory::WeaponInventory,
+    machete::{facing_angle, swing_hitbox},
+};
 
 // --------
 // Entities
 // --------
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component, Reflect)]
-pub struct Player {
-    pub health: u16,
-}
+pub struct Player;
 
 #[derive(Default, Bundle)]
 pub struct PlayerBundle {
@@ -27,12 +39,48 @@ pub struct PlayerBundle {
     pub character_animation: CharacterAnimation,
     pub animation_timer: AnimationTimer,
     pub player: Player,
+    pub health: Health,
     pub animated_character_sprite: AnimatedCharacterSprite,
     pub collider_bundle: ColliderBundle,
     pub active_events: ActiveEvents,
     pub name: Name,
 }
 
+/// The player can't be hit again until the timer finishes.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+/// Pushes the player away from what hit it, overriding the controls until the timer finishes.
+#[derive(Component)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    pub timer: Timer,
+}
+
+// ---------
+// Resources
+// ---------
+
+/// How the player reacts to getting hit.
+#[derive(Resource, Debug, Clone)]
+pub struct PlayerHitSettings {
+    /// Seconds the player can't be hit again after a hit.
+    pub invulnerability_secs: f32,
+    /// Pixels per second the player is pushed away from what hit it.
+    pub knockback_speed: f32,
+    pub knockback_secs: f32,
+}
+
+impl Default for PlayerHitSettings {
+    fn default() -> Self {
+        PlayerHitSettings {
+            invulnerability_secs: 1.0,
+            knockback_speed: 250.0,
+            knockback_secs: 0.15,
+        }
+    }
+}
+
 // ----
 // LDTK
 // ----
@@ -82,7 +130,8 @@ impl LdtkEntity for PlayerBundle {
             sprite_bundle,
             collider_bundle,
             active_events: ActiveEvents::COLLISION_EVENTS,
-            player: Player { health: 100 },
+            player: Player,
+            health: Health::new(100),
             animated_character_sprite: AnimatedCharacterSprite {
                 animated_character_type: AnimatedCharacterType::Player,
             },
@@ -104,67 +153,123 @@ pub struct PlayerAttackEvent {
 #[derive(Event, Clone)]
 pub struct PlayerHitEvent {
     pub entity: Entity,
+    pub damage: u16,
+    /// World position of what hit the player, it gets knocked back away from it.
+    pub source: Vec2,
 }
 
 // --------------
 // Event Handlers
 // --------------
 
+/// Swings the machete, hitting every enemy inside the cone in front of the player.
 pub fn event_player_attack(
-    mut commands: Commands,
     mut ev_player_attack: EventReader<PlayerAttackEvent>,
     mut ev_enemy_hit: EventWriter<EnemyHitEvent>,
-    mut q_player: Query<(Entity, &Transform, &CharacterAnimation), With<Player>>,
-    mut q_enemies: Query<(Entity, &Transform, &mut Enemy)>,
+    q_player: Query<(&GlobalTransform, &CharacterAnimation), (With<Player>, Without<Dead>)>,
+    q_enemies: Query<&Enemy, Without<Dead>>,
+    rapier_context: Res<RapierContext>,
+    inventory: Res<WeaponInventory>,
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
 ) {
+    let Some(machete) = inventory.slot(WeaponKind::Machete) else {
+        return;
+    };
+
     for ev in ev_player_attack.read() {
-        if commands.get_entity(ev.entity).is_none() {
+        let Ok((transform, char_animation)) = q_player.get(ev.entity) else {
             continue;
-        }
-
-        let (_, transform, char_animation) = q_player.get_mut(ev.entity).unwrap();
-
-        let player_position = transform.translation;
-        let _player_orientation = char_animation.direction;
+        };
+        let Some(hitbox) = machete
+            .kind
+            .definition()
+            .melee
+            .as_ref()
+            .and_then(|melee| swing_hitbox(melee, machete.range()))
+        else {
+            continue;
+        };
 
         audio.play(audio_assets.slash.clone());
 
-        // find all mierdas in range
-        for (entity, mierda_transform, _) in q_enemies.iter_mut().filter(|(_, _, m)| !m.is_dummy) {
-            let mierda_position = mierda_transform.translation;
-
-            let distance = player_position.distance(mierda_position);
-
-            if distance >= 45. {
-                continue;
-            }
-
-            ev_enemy_hit.send(EnemyHitEvent {
-                entity,
-                damage: 100,
-            });
-        }
+        rapier_context.intersections_with_shape(
+            transform.translation().truncate(),
+            facing_angle(char_animation.direction),
+            &hitbox,
+            QueryFilter::new().exclude_sensors(),
+            |entity| {
+                if q_enemies.get(entity).is_ok_and(|enemy| !enemy.is_dummy) {
+                    ev_enemy_hit.send(EnemyHitEvent {
+                        entity,
+                        damage: machete.damage(),
+                        effect: machete.kind.definition().on_hit,
+                    });
+                }
+                true
+            },
+        );
     }
 }
 
 pub fn event_player_hit(
     mut commands: Commands,
     mut ev_player_hit_reader: EventReader<PlayerHitEvent>,
-    mut ev_game_over: EventWriter<GameOverEvent>,
-    mut q_player: Query<(Entity, &GlobalTransform, &mut Player)>,
-    mut q_ui_healthbar: Query<(Entity, &mut Style, &UIPlayerHealth)>,
+    mut ew_damage: EventWriter<DamageEvent>,
+    q_player: Query<
+        (
+            &GlobalTransform,
+            Option<&Invulnerable>,
+            Option<&StatusEffects>,
+        ),
+        (With<Player>, Without<Dead>),
+    >,
+    mut combo: ResMut<Combo>,
+    mut ev_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+    hit_settings: Res<PlayerHitSettings>,
     asset_server: Res<AssetServer>,
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
 ) {
+    // components inserted below only show up next frame
+    let mut hit_this_frame = Vec::new();
+
     for ev in ev_player_hit_reader.read() {
-        if commands.get_entity(ev.entity).is_none() {
+        let Ok((player_transform, invulnerable, status_effects)) = q_player.get(ev.entity) else {
+            continue;
+        };
+
+        if invulnerable.is_some()
+            || status_effects.is_some_and(|effects| effects.is_shielded())
+            || hit_this_frame.contains(&ev.entity)
+        {
             continue;
         }
+        hit_this_frame.push(ev.entity);
+
+        let invulnerability =
+            Timer::from_seconds(hit_settings.invulnerability_secs, TimerMode::Once);
+        let knockback_direction =
+            (player_transform.translation().truncate() - ev.source).normalize_or_zero();
+
+        commands.entity(ev.entity).insert((
+            Invulnerable(invulnerability.clone()),
+            FlashingTimer {
+                timer: invulnerability,
+            },
+            Knockback {
+                velocity: knockback_direction * hit_settings.knockback_speed,
+                timer: Timer::from_seconds(hit_settings.knockback_secs, TimerMode::Once),
+            },
+        ));
 
-        let (_, player_transform, mut player) = q_player.get_mut(ev.entity).unwrap();
+        // taking damage breaks the combo
+        if combo.reset() {
+            ev_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+                text: "COMBO LOST".to_string(),
+                entity: ev.entity,
+            });
+        }
 
         commands.spawn((
             ParticleSystemBundle {
@@ -190,54 +295,131 @@ pub fn event_player_hit(
                 ..default()
             },
             Playing,
+            RunEntity,
         ));
 
         audio.play(audio_assets.hurt.clone()).with_volume(0.5);
 
-        if player.health == 0 {
-            ev_game_over.send(GameOverEvent);
+        ew_damage.send(DamageEvent {
+            entity: ev.entity,
+            amount: ev.damage,
+        });
+    }
+}
+
+/// Seconds the player's death animation plays before the game is over.
+const PLAYER_DEATH_SECS: f32 = 1.5;
+
+pub fn event_player_death(
+    mut commands: Commands,
+    mut er_death: EventReader<DeathEvent>,
+    mut q_player: Query<&mut Velocity, With<Player>>,
+) {
+    for ev in er_death.read() {
+        let Ok(mut velocity) = q_player.get_mut(ev.entity) else {
             continue;
-        } else {
-            player.health -= 1;
+        };
 
-            for (_, mut style, _) in q_ui_healthbar.iter_mut() {
-                style.width = Val::Percent(player.health as f32);
-            }
-        }
+        velocity.linvel = Vec2::ZERO;
+
+        commands
+            .entity(ev.entity)
+            .remove::<(Invulnerable, Knockback, FlashingTimer)>()
+            .insert(DeathAnimation::new(PLAYER_DEATH_SECS));
     }
 }
 
 // -------
-// Physics
+// Systems
 // -------
 
-pub fn handle_player_enemy_collisions(
-    mut collision_events: EventReader<CollisionEvent>,
-    q_player: Query<(Entity, &mut Player)>,
-    q_enemies: Query<(Entity, &Enemy)>,
-    mut ev_player_hit: EventWriter<PlayerHitEvent>,
+/// Ends the game once the player's death animation is over.
+pub fn finish_player_death(
+    q_player: Query<&DeathAnimation, With<Player>>,
+    mut ew_game_over: EventWriter<GameOverEvent>,
+) {
+    if q_player.iter().any(|animation| animation.0.just_finished()) {
+        ew_game_over.send(GameOverEvent {
+            reason: GameEndReason::PlayerDied,
+        });
+    }
+}
+
+pub fn update_health_bar(
+    q_player: Query<&Health, (With<Player>, Changed<Health>)>,
+    mut q_ui_healthbar: Query<&mut Style, With<UIPlayerHealth>>,
+) {
+    let Ok(health) = q_player.get_single() else {
+        return;
+    };
+
+    for mut style in q_ui_healthbar.iter_mut() {
+        style.width = Val::Percent(100.0 * health.fraction());
+    }
+}
+
+pub fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_player: Query<(Entity, &mut Invulnerable)>,
 ) {
-    for event in collision_events.read() {
-        if let CollisionEvent::Started(e1, e2, _) = event {
-            let contact_1_player = q_player.get(*e1);
-            let contact_2_player = q_player.get(*e2);
-            let is_contact_player = contact_1_player.is_ok() || contact_2_player.is_ok();
+    for (entity, mut invulnerable) in q_player.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
 
-            let contact_1_enemy = q_enemies.get(*e1);
-            let contact_2_enemy = q_enemies.get(*e2);
-            let is_contact_enemy = contact_1_enemy.is_ok() || contact_2_enemy.is_ok();
+pub fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_player: Query<(Entity, &mut Velocity, &mut Knockback)>,
+) {
+    for (entity, mut velocity, mut knockback) in q_player.iter_mut() {
+        velocity.linvel = knockback.velocity;
 
-            if !(is_contact_player && is_contact_enemy) {
-                continue;
-            }
+        if knockback.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
 
-            let player_entity = match contact_1_player.is_ok() {
-                true => contact_1_player.unwrap().0,
-                false => contact_2_player.unwrap().0,
-            };
+// -------
+// Physics
+// -------
 
+/// Hurts the player for as long as it touches an enemy, once per invulnerability window.
+pub fn handle_player_enemy_contacts(
+    rapier_context: Res<RapierContext>,
+    q_player: Query<Entity, (With<Player>, Without<Invulnerable>, Without<Dead>)>,
+    q_enemies: Query<(&GlobalTransform, &Enemy), Without<Dead>>,
+    mut ev_player_hit: EventWriter<PlayerHitEvent>,
+    enemy_definitions: EnemyDefinitions,
+) {
+    for player_entity in q_player.iter() {
+        // the enemy that hurts the most is the one that counts
+        let hit = rapier_context
+            .contact_pairs_with(player_entity)
+            .filter(|contact_pair| contact_pair.has_any_active_contacts())
+            .filter_map(|contact_pair| {
+                let other = match contact_pair.collider1() == player_entity {
+                    true => contact_pair.collider2(),
+                    false => contact_pair.collider1(),
+                };
+                q_enemies.get(other).ok()
+            })
+            .filter(|(_, enemy)| !enemy.is_dummy)
+            .map(|(transform, enemy)| {
+                let damage = enemy_definitions.get(enemy.enemy_type).contact_damage;
+                (damage, transform.translation().truncate())
+            })
+            .max_by_key(|(damage, _)| *damage);
+
+        if let Some((damage, source)) = hit {
             ev_player_hit.send(PlayerHitEvent {
                 entity: player_entity,
+                damage,
+                source,
             });
         }
     }
@@ -252,6 +434,7 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.register_ldtk_entity::<PlayerBundle>("Player")
+            .init_resource::<PlayerHitSettings>()
             // Events
             .add_event::<PlayerAttackEvent>()
             .add_event::<PlayerHitEvent>()
@@ -260,8 +443,13 @@ impl Plugin for PlayerPlugin {
                 Update,
                 (
                     event_player_attack,
-                    event_player_hit,
-                    handle_player_enemy_collisions,
+                    handle_player_enemy_contacts,
+                    event_player_hit.after(handle_player_enemy_contacts),
+                    tick_invulnerability.after(event_player_hit),
+                    apply_knockback.after(ControlSet::Apply),
+                    event_player_death.after(apply_health_changes),
+                    finish_player_death.after(animate_deaths),
+                    update_health_bar.after(apply_health_changes),
                     // handle_machete_attack,
                 )
                     .run_if(in_state(GameState::GamePlay)),
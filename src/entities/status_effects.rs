@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    sprites::{flash_sprite, FlashingTimer},
+    GameState,
+};
+
+use super::{
+    characters::enemy::handle_enemy_hit,
+    health::{apply_health_changes, DamageEvent, Dead, DeathAnimation},
+    text_indicator::SpawnTextIndicatorEvent,
+};
+
+/// Speed lost to every stack of [`StatusEffectKind::Slow`].
+const SLOW_PER_STACK: f32 = 0.2;
+/// Speed gained from [`StatusEffectKind::Haste`].
+const HASTE_BONUS: f32 = 0.5;
+
+// ----------
+// Definition
+// ----------
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusEffectKind {
+    /// Moves slower, stacks.
+    Slow,
+    /// Can't move or attack.
+    Stun,
+    /// Loses health over time.
+    Burn,
+    /// Loses health over time, stacks.
+    Poison,
+    /// Moves the opposite way.
+    Confusion,
+    /// Moves faster.
+    Haste,
+    /// Ignores hits.
+    Shield,
+}
+
+impl StatusEffectKind {
+    /// The tint of the first effect an entity has in this order is shown.
+    const TINT_ORDER: [StatusEffectKind; 7] = [
+        StatusEffectKind::Shield,
+        StatusEffectKind::Stun,
+        StatusEffectKind::Burn,
+        StatusEffectKind::Poison,
+        StatusEffectKind::Confusion,
+        StatusEffectKind::Slow,
+        StatusEffectKind::Haste,
+    ];
+
+    /// Applying an effect again adds a stack up to this many, it always refreshes the duration.
+    pub fn max_stacks(&self) -> u32 {
+        match self {
+            StatusEffectKind::Slow => 3,
+            StatusEffectKind::Poison => 5,
+            _ => 1,
+        }
+    }
+
+    /// Damage per stack and seconds between two ticks.
+    pub fn tick_damage(&self) -> Option<(u16, f32)> {
+        match self {
+            StatusEffectKind::Burn => Some((4, 0.5)),
+            StatusEffectKind::Poison => Some((2, 1.0)),
+            _ => None,
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            StatusEffectKind::Slow => Color::rgb(0.5, 0.7, 1.0),
+            StatusEffectKind::Stun => Color::rgb(1.0, 1.0, 0.4),
+            StatusEffectKind::Burn => Color::rgb(1.0, 0.5, 0.2),
+            StatusEffectKind::Poison => Color::rgb(0.5, 1.0, 0.4),
+            StatusEffectKind::Confusion => Color::rgb(0.8, 0.5, 1.0),
+            StatusEffectKind::Haste => Color::rgb(0.6, 1.0, 1.0),
+            StatusEffectKind::Shield => Color::rgb(1.0, 0.85, 0.3),
+        }
+    }
+}
+
+/// An effect a weapon or projectile applies to what it hits.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StatusEffectHit {
+    pub kind: StatusEffectKind,
+    /// Seconds.
+    pub duration: f32,
+}
+
+// ----------
+// Components
+// ----------
+
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub stacks: u32,
+    pub timer: Timer,
+    tick_timer: Option<Timer>,
+}
+
+/// Effects currently on the player or an enemy, changed through [`ApplyStatusEffectEvent`].
+#[derive(Component, Clone, Default, Debug)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    pub fn apply(&mut self, hit: StatusEffectHit) {
+        if let Some(effect) = self.0.iter_mut().find(|effect| effect.kind == hit.kind) {
+            effect.stacks = (effect.stacks + 1).min(hit.kind.max_stacks());
+            if effect.timer.remaining_secs() < hit.duration {
+                effect.timer = Timer::from_seconds(hit.duration, TimerMode::Once);
+            }
+            return;
+        }
+
+        self.0.push(StatusEffect {
+            kind: hit.kind,
+            stacks: 1,
+            timer: Timer::from_seconds(hit.duration, TimerMode::Once),
+            tick_timer: hit
+                .kind
+                .tick_damage()
+                .map(|(_, interval)| Timer::from_seconds(interval, TimerMode::Repeating)),
+        });
+    }
+
+    pub fn has(&self, kind: StatusEffectKind) -> bool {
+        self.0.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusEffectKind::Stun)
+    }
+
+    pub fn is_shielded(&self) -> bool {
+        self.has(StatusEffectKind::Shield)
+    }
+
+    /// Multiplies the movement of the entity, negative while confused.
+    pub fn speed_multiplier(&self) -> f32 {
+        let slow = self
+            .0
+            .iter()
+            .find(|effect| effect.kind == StatusEffectKind::Slow)
+            .map_or(0, |effect| effect.stacks);
+        let mut multiplier = (1.0 - SLOW_PER_STACK * slow as f32).max(0.0);
+        if self.has(StatusEffectKind::Haste) {
+            multiplier *= 1.0 + HASTE_BONUS;
+        }
+
+        if self.has(StatusEffectKind::Confusion) {
+            -multiplier
+        } else {
+            multiplier
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        StatusEffectKind::TINT_ORDER
+            .into_iter()
+            .find(|kind| self.has(*kind))
+            .map_or(Color::WHITE, |kind| kind.tint())
+    }
+}
+
+// ------
+// Events
+// ------
+
+#[derive(Event, Clone)]
+pub struct ApplyStatusEffectEvent {
+    pub entity: Entity,
+    pub effect: StatusEffectHit,
+}
+
+// --------------
+// Event Handlers
+// --------------
+
+pub fn event_apply_status_effect(
+    mut commands: Commands,
+    mut er_apply: EventReader<ApplyStatusEffectEvent>,
+    mut q_effects: Query<&mut StatusEffects, Without<Dead>>,
+    q_dead: Query<(), With<Dead>>,
+) {
+    // entities getting their first effect, the component only shows up next frame
+    let mut inserted: HashMap<Entity, StatusEffects> = HashMap::new();
+
+    for ev in er_apply.read() {
+        if let Ok(mut effects) = q_effects.get_mut(ev.entity) {
+            effects.apply(ev.effect);
+        } else if commands.get_entity(ev.entity).is_some() && !q_dead.contains(ev.entity) {
+            inserted.entry(ev.entity).or_default().apply(ev.effect);
+        }
+    }
+
+    for (entity, effects) in inserted {
+        commands.entity(entity).insert(effects);
+    }
+}
+
+// -------
+// Systems
+// -------
+
+/// Runs out the effects and deals their damage over time.
+fn tick_status_effects(
+    time: Res<Time>,
+    mut q_effects: Query<(Entity, &mut StatusEffects), Without<Dead>>,
+    mut ew_damage: EventWriter<DamageEvent>,
+    mut ev_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    for (entity, mut effects) in q_effects.iter_mut() {
+        if effects.0.is_empty() {
+            continue;
+        }
+
+        for effect in effects.0.iter_mut() {
+            effect.timer.tick(time.delta());
+
+            let (Some(tick_timer), Some((damage, _))) =
+                (effect.tick_timer.as_mut(), effect.kind.tick_damage())
+            else {
+                continue;
+            };
+            if !tick_timer.tick(time.delta()).just_finished() {
+                continue;
+            }
+
+            let amount = damage * effect.stacks as u16;
+            ew_damage.send(DamageEvent { entity, amount });
+            ev_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+                text: format!("-{}", amount),
+                entity,
+            });
+        }
+
+        effects.0.retain(|effect| !effect.timer.finished());
+    }
+}
+
+/// Tints the sprite with its strongest effect, hit flashes and death fades take precedence.
+fn tint_status_effects(
+    mut q_sprites: Query<
+        (&StatusEffects, &mut TextureAtlasSprite),
+        (Without<FlashingTimer>, Without<DeathAnimation>),
+    >,
+) {
+    for (effects, mut sprite) in q_sprites.iter_mut() {
+        let tint = effects.tint();
+        if sprite.color != tint {
+            sprite.color = tint;
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct StatusEffectsPlugin;
+
+impl Plugin for StatusEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatusEffectEvent>().add_systems(
+            Update,
+            (
+                event_apply_status_effect.after(handle_enemy_hit),
+                tick_status_effects
+                    .after(event_apply_status_effect)
+                    .before(apply_health_changes),
+                tint_status_effects.after(flash_sprite),
+            )
+                .run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(kind: StatusEffectKind, duration: f32) -> StatusEffectHit {
+        StatusEffectHit { kind, duration }
+    }
+
+    #[test]
+    fn stacks_stop_at_the_max_stacks() {
+        let mut effects = StatusEffects::default();
+        for _ in 0..10 {
+            effects.apply(hit(StatusEffectKind::Poison, 2.0));
+            effects.apply(hit(StatusEffectKind::Stun, 2.0));
+        }
+
+        assert_eq!(effects.0.len(), 2);
+        for effect in effects.0.iter() {
+            assert_eq!(effect.stacks, effect.kind.max_stacks());
+        }
+    }
+
+    #[test]
+    fn refresh_only_extends_the_duration() {
+        let mut effects = StatusEffects::default();
+        let remaining = |effects: &StatusEffects| effects.0[0].timer.remaining_secs();
+
+        effects.apply(hit(StatusEffectKind::Slow, 3.0));
+        effects.apply(hit(StatusEffectKind::Slow, 1.0));
+        assert_eq!(remaining(&effects), 3.0);
+
+        effects.apply(hit(StatusEffectKind::Slow, 5.0));
+        assert_eq!(remaining(&effects), 5.0);
+    }
+
+    #[test]
+    fn slow_stacks_combine_with_haste_and_confusion() {
+        let mut effects = StatusEffects::default();
+        let assert_multiplier = |effects: &StatusEffects, expected: f32| {
+            let multiplier = effects.speed_multiplier();
+            assert!((multiplier - expected).abs() < 1e-5, "{}", multiplier);
+        };
+        assert_multiplier(&effects, 1.0);
+
+        for _ in 0..3 {
+            effects.apply(hit(StatusEffectKind::Slow, 2.0));
+        }
+        assert_multiplier(&effects, 1.0 - 3.0 * SLOW_PER_STACK);
+
+        effects.apply(hit(StatusEffectKind::Haste, 2.0));
+        assert_multiplier(&effects, (1.0 - 3.0 * SLOW_PER_STACK) * (1.0 + HASTE_BONUS));
+
+        effects.apply(hit(StatusEffectKind::Confusion, 2.0));
+        assert_multiplier(
+            &effects,
+            -(1.0 - 3.0 * SLOW_PER_STACK) * (1.0 + HASTE_BONUS),
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::entities::status_effects::{StatusEffectHit, StatusEffectKind};
+
+// -----------
+// Definitions
+// -----------
+
+/// A weapon the player can carry in its [`WeaponInventory`](super::inventory::WeaponInventory).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect, Deserialize)]
+pub enum WeaponKind {
+    Machete,
+    Speargun,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 2] = [WeaponKind::Machete, WeaponKind::Speargun];
+
+    pub fn definition(&self) -> &'static WeaponDefinition {
+        match self {
+            WeaponKind::Machete => &MACHETE,
+            WeaponKind::Speargun => &SPEARGUN,
+        }
+    }
+}
+
+/// Stats of a [`WeaponKind`].
+#[derive(Debug, Clone)]
+pub struct WeaponDefinition {
+    pub name: &'static str,
+    /// Shown in the HUD and as the pickup in the level.
+    pub icon: &'static str,
+    pub damage: u16,
+    /// Seconds between two automatic attacks.
+    pub cooldown: f32,
+    /// Reach in pixels, how far a melee attack hits or a projectile flies.
+    pub range: f32,
+    /// Applied to every enemy the weapon hits.
+    pub on_hit: Option<StatusEffectHit>,
+    pub melee: Option<MeleeDefinition>,
+    pub projectile: Option<ProjectileDefinition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MeleeDefinition {
+    /// Radians of the cone hit by a swing, centered on the direction the player faces.
+    pub arc: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectileDefinition {
+    /// Pixels per second.
+    pub speed: f32,
+    /// Seconds before the projectile is despawned.
+    pub lifetime: f32,
+    /// Half extents of the collider.
+    pub collider: Vec2,
+    /// Enemies a projectile passes through before it breaks, 0 stops at the first one.
+    pub pierce: u32,
+    /// Projectiles fired in every attack, fanned out around the aim.
+    pub count: u32,
+    /// Radians between two projectiles of the same attack.
+    pub spread: f32,
+}
+
+const MACHETE: WeaponDefinition = WeaponDefinition {
+    name: "MACHETE",
+    icon: "sprites/machete.png",
+    damage: 100,
+    cooldown: 1.0,
+    range: 45.0,
+    on_hit: None,
+    melee: Some(MeleeDefinition { arc: 2.1 }),
+    projectile: None,
+};
+
+const SPEARGUN: WeaponDefinition = WeaponDefinition {
+    name: "SPEARGUN",
+    icon: "sprites/speargun.png",
+    damage: 50,
+    cooldown: 1.0,
+    range: 350.0,
+    on_hit: Some(StatusEffectHit {
+        kind: StatusEffectKind::Slow,
+        duration: 2.0,
+    }),
+    melee: None,
+    projectile: Some(ProjectileDefinition {
+        speed: 350.0,
+        lifetime: 1.0,
+        collider: Vec2::new(20.0, 5.0),
+        pierce: 1,
+        count: 1,
+        spread: 0.15,
+    }),
+};
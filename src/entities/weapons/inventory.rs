@@ -0,0 +1,387 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    entities::{player::Player, text_indicator::SpawnTextIndicatorEvent},
+    gameplay::{
+        rng::{GameRng, GameRngSet},
+        run::{reset_resource, StartRun, StartRunSet},
+        spawn_pattern::{PlayerSpawnArea, SpawnAreaParam, SpawnPattern},
+    },
+    ui::{UIWeaponCooldown, UIWeaponName, UIWeaponSlots},
+    GameState,
+};
+
+use super::definition::WeaponKind;
+
+/// Most weapons the player can have equipped at once.
+pub const MAX_WEAPON_SLOTS: usize = 3;
+
+// ---------
+// Resources
+// ---------
+
+#[derive(Debug, Clone)]
+pub struct WeaponSlot {
+    pub kind: WeaponKind,
+    /// Repeating, the weapon attacks every time it finishes.
+    pub cooldown: Timer,
+    /// Multiplies the damage of the definition, raised by level-up upgrades.
+    pub damage_multiplier: f32,
+    /// Multiplies the range of the definition and the size of its projectiles.
+    pub area_multiplier: f32,
+}
+
+impl WeaponSlot {
+    pub fn damage(&self) -> u16 {
+        (self.kind.definition().damage as f32 * self.damage_multiplier) as u16
+    }
+
+    pub fn range(&self) -> f32 {
+        self.kind.definition().range * self.area_multiplier
+    }
+}
+
+/// Weapons the player has equipped, kept from one level of the run to the next.
+#[derive(Resource, Debug, Clone)]
+pub struct WeaponInventory {
+    pub slots: Vec<WeaponSlot>,
+}
+
+impl Default for WeaponInventory {
+    fn default() -> Self {
+        let mut inventory = WeaponInventory { slots: Vec::new() };
+        inventory.equip(WeaponKind::Machete);
+        inventory
+    }
+}
+
+impl WeaponInventory {
+    pub fn has(&self, kind: WeaponKind) -> bool {
+        self.slots.iter().any(|slot| slot.kind == kind)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.len() >= MAX_WEAPON_SLOTS
+    }
+
+    /// `false` if the weapon is already equipped or every slot is taken.
+    pub fn equip(&mut self, kind: WeaponKind) -> bool {
+        if self.has(kind) || self.is_full() {
+            return false;
+        }
+
+        self.slots.push(WeaponSlot {
+            kind,
+            cooldown: Timer::from_seconds(kind.definition().cooldown, TimerMode::Repeating),
+            damage_multiplier: 1.0,
+            area_multiplier: 1.0,
+        });
+        true
+    }
+
+    /// Whether the weapon is equipped and its cooldown finished this frame.
+    pub fn attacks(&self, kind: WeaponKind) -> bool {
+        self.slots
+            .iter()
+            .any(|slot| slot.kind == kind && slot.cooldown.just_finished())
+    }
+
+    pub fn slot(&self, kind: WeaponKind) -> Option<&WeaponSlot> {
+        self.slots.iter().find(|slot| slot.kind == kind)
+    }
+
+    pub fn slot_mut(&mut self, kind: WeaponKind) -> Option<&mut WeaponSlot> {
+        self.slots.iter_mut().find(|slot| slot.kind == kind)
+    }
+}
+
+// ----------
+// Components
+// ----------
+
+/// A weapon lying in the level, equipped when the player walks over it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct WeaponPickup {
+    pub kind: WeaponKind,
+}
+
+// ------
+// Events
+// ------
+
+/// Equips a weapon, from a pickup or a level-up reward.
+#[derive(Event, Clone)]
+pub struct AcquireWeaponEvent {
+    pub kind: WeaponKind,
+    /// Despawned once the weapon is equipped, left in the level otherwise.
+    pub pickup: Option<Entity>,
+}
+
+#[derive(Event, Clone)]
+pub struct SpawnWeaponPickupEvent {
+    pub count: u32,
+    pub kind: WeaponKind,
+    pub pattern: SpawnPattern,
+}
+
+// --------------
+// Event Handlers
+// --------------
+
+fn event_acquire_weapon(
+    mut commands: Commands,
+    mut er_acquire_weapon: EventReader<AcquireWeaponEvent>,
+    mut inventory: ResMut<WeaponInventory>,
+    q_player: Query<Entity, With<Player>>,
+    mut ew_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    for ev in er_acquire_weapon.read() {
+        if inventory.has(ev.kind) {
+            continue;
+        }
+
+        let equipped = inventory.equip(ev.kind);
+        let text = match equipped {
+            true => ev.kind.definition().name,
+            false => "NO FREE WEAPON SLOT",
+        };
+
+        if let (true, Some(pickup)) = (equipped, ev.pickup) {
+            commands.entity(pickup).despawn_recursive();
+        }
+
+        for player in q_player.iter() {
+            ew_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+                text: text.to_string(),
+                entity: player,
+            });
+        }
+    }
+}
+
+fn event_spawn_weapon_pickup(
+    mut commands: Commands,
+    mut er_spawn_weapon_pickup: EventReader<SpawnWeaponPickupEvent>,
+    spawn_area: SpawnAreaParam,
+    asset_server: Res<AssetServer>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let Some(PlayerSpawnArea { layer, z, area }) = spawn_area.get(1.0 / 3.0) else {
+        return;
+    };
+
+    for ev in er_spawn_weapon_pickup.read() {
+        for position in area.positions(&ev.pattern, ev.count as usize, &mut *game_rng) {
+            let definition = ev.kind.definition();
+
+            commands.entity(layer).with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: asset_server.load(definition.icon),
+                        transform: Transform::from_translation(position.extend(z)),
+                        ..default()
+                    },
+                    WeaponPickup { kind: ev.kind },
+                    Collider::cuboid(32.0, 8.0),
+                    Sensor,
+                    Name::new(format!("{} pickup", definition.name)),
+                ));
+            });
+        }
+    }
+}
+
+// -------
+// Systems
+// -------
+
+pub fn tick_weapon_cooldowns(time: Res<Time>, mut inventory: ResMut<WeaponInventory>) {
+    // the HUD only redraws the slots when a weapon is equipped
+    for slot in inventory.bypass_change_detection().slots.iter_mut() {
+        slot.cooldown.tick(time.delta());
+    }
+}
+
+fn draw_weapon_slots(
+    mut commands: Commands,
+    inventory: Res<WeaponInventory>,
+    q_slots: Query<(Entity, Ref<UIWeaponSlots>)>,
+    asset_server: Res<AssetServer>,
+) {
+    for (slots_entity, slots) in q_slots.iter() {
+        if !(inventory.is_changed() || slots.is_added()) {
+            continue;
+        }
+
+        commands
+            .entity(slots_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for (index, slot) in inventory.slots.iter().enumerate() {
+                    let definition = slot.kind.definition();
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::FlexEnd,
+                                margin: UiRect::top(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Px(160.0),
+                                        height: Val::Px(22.5),
+                                        ..default()
+                                    },
+                                    background_color: Color::WHITE.into(),
+                                    ..default()
+                                },
+                                UiImage::new(asset_server.load(definition.icon)),
+                            ));
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    definition.name,
+                                    TextStyle {
+                                        font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
+                                        font_size: 20.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                UIWeaponName,
+                            ));
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        width: Val::Px(160.0),
+                                        height: Val::Px(4.0),
+                                        ..default()
+                                    },
+                                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.2).into(),
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        NodeBundle {
+                                            style: Style {
+                                                width: Val::Percent(0.0),
+                                                height: Val::Percent(100.0),
+                                                ..default()
+                                            },
+                                            background_color: Color::WHITE.into(),
+                                            ..default()
+                                        },
+                                        UIWeaponCooldown { slot: index },
+                                    ));
+                                });
+                        });
+                }
+            });
+    }
+}
+
+fn update_weapon_cooldowns(
+    inventory: Res<WeaponInventory>,
+    mut q_cooldowns: Query<(&mut Style, &UIWeaponCooldown)>,
+) {
+    for (mut style, cooldown) in q_cooldowns.iter_mut() {
+        if let Some(slot) = inventory.slots.get(cooldown.slot) {
+            style.width = Val::Percent(100.0 * slot.cooldown.percent());
+        }
+    }
+}
+
+// -------
+// Physics
+// -------
+
+fn handle_player_weapon_pickup_collisions(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_player: Query<(), With<Player>>,
+    q_pickups: Query<&WeaponPickup>,
+    mut ew_acquire_weapon: EventWriter<AcquireWeaponEvent>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+
+        let pickup_entity = match (q_player.contains(*e1), q_player.contains(*e2)) {
+            (true, _) => *e2,
+            (_, true) => *e1,
+            _ => continue,
+        };
+        let Ok(pickup) = q_pickups.get(pickup_entity) else {
+            continue;
+        };
+
+        ew_acquire_weapon.send(AcquireWeaponEvent {
+            kind: pickup.kind,
+            pickup: Some(pickup_entity),
+        });
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct WeaponInventoryPlugin;
+
+impl Plugin for WeaponInventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeaponInventory>()
+            .add_event::<AcquireWeaponEvent>()
+            .add_event::<SpawnWeaponPickupEvent>()
+            .add_systems(
+                StartRun,
+                reset_resource::<WeaponInventory>.in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    tick_weapon_cooldowns,
+                    event_acquire_weapon.after(handle_player_weapon_pickup_collisions),
+                    event_spawn_weapon_pickup.in_set(GameRngSet::SpawnItems),
+                    handle_player_weapon_pickup_collisions,
+                    draw_weapon_slots.after(event_acquire_weapon),
+                    update_weapon_cooldowns
+                        .after(draw_weapon_slots)
+                        .after(tick_weapon_cooldowns),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equip_rejects_weapons_already_equipped() {
+        let mut inventory = WeaponInventory::default();
+
+        assert!(!inventory.equip(WeaponKind::Machete));
+        assert!(inventory.equip(WeaponKind::Speargun));
+        assert!(!inventory.equip(WeaponKind::Speargun));
+        assert_eq!(inventory.slots.len(), 2);
+    }
+
+    #[test]
+    fn equip_rejects_weapons_when_every_slot_is_taken() {
+        let machete = WeaponInventory::default().slots[0].clone();
+        let mut inventory = WeaponInventory {
+            slots: vec![machete; MAX_WEAPON_SLOTS],
+        };
+
+        assert!(inventory.is_full());
+        assert!(!inventory.equip(WeaponKind::Speargun));
+        assert!(!inventory.has(WeaponKind::Speargun));
+    }
+}
@@ -1,10 +1,22 @@
-use std::time::Duration;
+use std::f32::consts::{FRAC_PI_2, PI};
 
+use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 use bevy_particle_systems::Lerpable;
+use bevy_rapier2d::prelude::*;
 
+use crate::sprites::{AnimationDirection, CharacterAnimation};
 use crate::GameState;
-use crate::{controls::ControlEvent, entities::player::Player};
+use crate::{
+    controls::{ControlEvent, ControlSet},
+    entities::player::Player,
+};
+
+use super::definition::{MeleeDefinition, WeaponKind};
+use super::inventory::{tick_weapon_cooldowns, WeaponInventory};
+
+/// Straight edges the arc of a swing is made of.
+const SWING_SEGMENTS: usize = 8;
 
 // note to self: attack happens every 1.3 seconds but there is delay
 // for attack 0.3 secodns so i hinda hack this around
@@ -16,6 +28,67 @@ use crate::{controls::ControlEvent, entities::player::Player};
 #[derive(Component, Clone, Copy, Default)]
 pub struct Machete {}
 
+// -----
+// Swing
+// -----
+
+/// Angle the player faces, 0 is to the right.
+pub fn facing_angle(direction: AnimationDirection) -> f32 {
+    match direction {
+        AnimationDirection::Right => 0.0,
+        AnimationDirection::Up => FRAC_PI_2,
+        AnimationDirection::Left => PI,
+        AnimationDirection::Down => -FRAC_PI_2,
+    }
+}
+
+/// Outline of a swing facing right with a reach of 1: the player's position followed by
+/// the points along the arc. The hitbox and the indicator are both built from it.
+fn swing_outline(melee: &MeleeDefinition) -> Vec<Vec2> {
+    let mut outline = vec![Vec2::ZERO];
+    outline.extend((0..=SWING_SEGMENTS).map(|segment| {
+        let angle = melee.arc * (segment as f32 / SWING_SEGMENTS as f32 - 0.5);
+        Vec2::new(angle.cos(), angle.sin())
+    }));
+    outline
+}
+
+/// Hitbox of a swing facing right, reaching `range` pixels.
+pub fn swing_hitbox(melee: &MeleeDefinition, range: f32) -> Option<Collider> {
+    let points: Vec<Vec2> = swing_outline(melee)
+        .into_iter()
+        .map(|point| point * range)
+        .collect();
+
+    Collider::convex_hull(&points)
+}
+
+/// Triangle fan over the swing outline, scaled to the range by the indicator's transform.
+fn swing_mesh(melee: &MeleeDefinition) -> Mesh {
+    let outline = swing_outline(melee);
+    let positions: Vec<[f32; 3]> = outline
+        .iter()
+        .map(|point| [point.x, point.y, 0.0])
+        .collect();
+    let uvs: Vec<[f32; 2]> = outline
+        .iter()
+        .map(|point| [(point.x + 1.0) / 2.0, (1.0 - point.y) / 2.0])
+        .collect();
+    let indices = (1..outline.len() as u32 - 1)
+        .flat_map(|i| [0, i, i + 1])
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_NORMAL,
+        vec![[0.0, 0.0, 1.0]; positions.len()],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
 // -------
 // Bundles
 // -------
@@ -24,16 +97,8 @@ pub struct Machete {}
 pub struct MacheteIndictorBundle {
     pub material_mesh_2d_bundle: MaterialMesh2dBundle<ColorMaterial>,
     pub machete_indicator: Machete,
-    pub timer_activation: MacheteTimer,
 }
 
-// ---------
-// Resources
-// ---------
-
-#[derive(Resource, Default, Clone, Component)]
-pub struct MacheteTimer(pub Timer);
-
 // -------
 // Systems
 // -------
@@ -44,21 +109,25 @@ fn inject_machete_indicator(
     mut q_machate_indicator: ParamSet<(Query<(&mut Transform, &Machete), Without<Player>>,)>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    inventory: Res<WeaponInventory>,
 ) {
+    if !inventory.has(WeaponKind::Machete) {
+        return;
+    }
+    let melee = WeaponKind::Machete
+        .definition()
+        .melee
+        .as_ref()
+        .expect("the machete is a melee weapon");
+
     for (entity, _parent, _player_transform, _) in q_players.iter() {
         if q_machate_indicator.p0().iter().count() == 0 {
-            let machete_timer = MacheteTimer(Timer::new(
-                Duration::from_secs_f32(1.0),
-                TimerMode::Repeating,
-            ));
-
             commands.entity(entity).with_children(|parent| {
                 parent.spawn((
                     MacheteIndictorBundle {
                         machete_indicator: Machete {},
-                        timer_activation: machete_timer.clone(),
                         material_mesh_2d_bundle: MaterialMesh2dBundle {
-                            mesh: meshes.add(shape::Circle::new(80.).into()).into(),
+                            mesh: meshes.add(swing_mesh(melee)).into(),
                             material: materials.add(ColorMaterial::from(Color::PURPLE.with_a(0.5))),
                             ..default()
                         },
@@ -72,29 +141,38 @@ fn inject_machete_indicator(
 }
 
 pub fn handle_machete_attack(
-    time: Res<Time>,
-    mut q_machete: Query<(Entity, &Transform, &mut MacheteTimer), With<Machete>>,
+    inventory: Res<WeaponInventory>,
     mut ev_control: EventWriter<ControlEvent>,
 ) {
-    for (_, _, mut machete_timer) in q_machete.iter_mut() {
-        machete_timer.0.tick(time.delta());
-
-        if machete_timer.0.just_finished() {
-            ev_control.send(ControlEvent {
-                attack: true,
-                ..Default::default()
-            });
-        }
+    if inventory.attacks(WeaponKind::Machete) {
+        ev_control.send(ControlEvent {
+            attack: true,
+            ..Default::default()
+        });
     }
 }
 
+/// Turns the indicator with the player and fades it in as the next swing comes closer.
 fn animate_machete_indicator(
-    mut q_machete: Query<(Entity, &mut Handle<ColorMaterial>, &mut MacheteTimer), With<Machete>>,
+    mut q_machete: Query<(&Parent, &mut Transform, &mut Handle<ColorMaterial>), With<Machete>>,
+    q_players: Query<&CharacterAnimation, With<Player>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    inventory: Res<WeaponInventory>,
 ) {
-    for (_, mut material, timer) in q_machete.iter_mut() {
-        let elapsed = timer.0.elapsed_secs();
-        let mut percentage = (1.0 - elapsed) / (1.0 - 0.3);
+    let Some(slot) = inventory.slot(WeaponKind::Machete) else {
+        return;
+    };
+    let timer = &slot.cooldown;
+
+    for (parent, mut transform, mut material) in q_machete.iter_mut() {
+        transform.scale = Vec3::splat(slot.range());
+        if let Ok(char_animation) = q_players.get(parent.get()) {
+            transform.rotation = Quat::from_rotation_z(facing_angle(char_animation.direction));
+        }
+
+        let cooldown = timer.duration().as_secs_f32();
+        let elapsed = timer.elapsed_secs();
+        let mut percentage = (cooldown - elapsed) / (cooldown - 0.3);
         if elapsed < 0.3 {
             percentage = 0.0;
         }
@@ -114,13 +192,15 @@ pub struct MachetePlugin;
 
 impl Plugin for MachetePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<MacheteTimer>()
+        app
             // Event Handlers
             .add_systems(
                 Update,
                 (
                     inject_machete_indicator,
-                    handle_machete_attack,
+                    handle_machete_attack
+                        .after(tick_weapon_cooldowns)
+                        .in_set(ControlSet::Input),
                     animate_machete_indicator,
                 )
                     .run_if(in_state(GameState::GamePlay)),
@@ -1,3 +1,5 @@
+pub mod definition;
+pub mod inventory;
 pub mod machete;
 pub mod pill;
 pub mod speargun;
@@ -11,6 +13,7 @@ impl Plugin for WeaponsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
             // weapon_arrow::WeaponArrowPlugin,
+            inventory::WeaponInventoryPlugin,
             speargun::WeaponSpeargunPlugin,
             pill::WeaponPillPlugin,
             machete::MachetePlugin,
@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use crate::entities::characters::enemy::{Enemy, EnemyType};
 use crate::entities::player::{Player, PlayerHitEvent};
+use crate::entities::status_effects::{ApplyStatusEffectEvent, StatusEffectHit, StatusEffectKind};
+use crate::gameplay::run::{reset_resource, StartRun, StartRunSet};
 use crate::physics::ColliderBundle;
 use crate::{loading::StaticSpriteAssets, GameState};
 
@@ -31,6 +33,13 @@ pub struct PillThrowEvent {
     pub entity: Entity,
 }
 
+/// Throws `pills` pills from `entity`, evenly spread in every direction.
+#[derive(Event, Clone)]
+pub struct PillBarrageEvent {
+    pub entity: Entity,
+    pub pills: usize,
+}
+
 // -------
 // Bundles
 // -------
@@ -158,49 +167,15 @@ fn handle_pill_throw_event(
 
         let throw_vector = player_position - pill_initial_position.normalize();
 
+        let pill_velocity = 1500.0;
         commands.entity(parent).with_children(|parent| {
-            let timer_despawn = PillDespawnTimer(Timer::new(
-                Duration::from_secs_f32(1.0),
-                TimerMode::Repeating,
-            ));
-
-            let timer_trail_spawn = PillTrailSpawnTimer(Timer::new(
-                Duration::from_millis(TRAIL_TIMER_SPAWN_MILLIS),
-                TimerMode::Repeating,
-            ));
-
-            let pill_velocity = 1500.0;
             parent.spawn((
-                PillBundle {
-                    sprite_bundle: SpriteBundle {
-                        texture: static_sprite_assets.pill.clone(),
-                        transform: Transform {
-                            translation: pill_initial_position,
-                            scale: Vec3::ONE * 0.5,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    pill: Pill,
-                    active_events: ActiveEvents::COLLISION_EVENTS,
-                    timer_despawn,
-                    timer_trail_spawn,
-                    collider_bundle: ColliderBundle {
-                        collider: Collider::cuboid(10., 5.),
-                        rigid_body: RigidBody::Dynamic,
-                        friction: Friction {
-                            coefficient: 0.0,
-                            combine_rule: CoefficientCombineRule::Min,
-                        },
-                        density: ColliderMassProperties::Density(105.0),
-                        rotation_constraints: LockedAxes::ROTATION_LOCKED_X,
-                        velocity: Velocity {
-                            linvel: pill_velocity * throw_vector.truncate(),
-                            angvel: 0.0,
-                        },
-                        ..default()
-                    },
-                },
+                pill_bundle(
+                    &static_sprite_assets,
+                    pill_initial_position,
+                    pill_velocity * throw_vector.truncate(),
+                    1.0,
+                ),
                 Name::new("weapon pill"),
                 ZIndex::Local(202),
             ));
@@ -208,6 +183,94 @@ fn handle_pill_throw_event(
     }
 }
 
+const BARRAGE_PILL_VELOCITY: f32 = 300.0;
+const BARRAGE_PILL_LIFETIME_SECS: f32 = 3.0;
+/// Pills of a barrage start on a circle around the thrower, clear of its collider.
+const BARRAGE_RADIUS: f32 = 48.0;
+
+fn handle_pill_barrage_event(
+    mut commands: Commands,
+    q_enemies: Query<(&Parent, &Transform), With<Enemy>>,
+    mut ev_pill_barrage: EventReader<PillBarrageEvent>,
+    static_sprite_assets: Res<StaticSpriteAssets>,
+    time: Res<Time>,
+) {
+    for event in ev_pill_barrage.read() {
+        let Ok((parent, transform)) = q_enemies.get(event.entity) else {
+            continue;
+        };
+
+        // turn every barrage a bit so the gaps are never in the same place
+        let offset = time.elapsed_seconds();
+
+        commands.entity(parent.get()).with_children(|parent| {
+            for index in 0..event.pills {
+                let angle = offset + index as f32 * std::f32::consts::TAU / event.pills as f32;
+                let direction = Vec2::from_angle(angle);
+
+                parent.spawn((
+                    pill_bundle(
+                        &static_sprite_assets,
+                        transform.translation + (BARRAGE_RADIUS * direction).extend(0.0),
+                        BARRAGE_PILL_VELOCITY * direction,
+                        BARRAGE_PILL_LIFETIME_SECS,
+                    ),
+                    Name::new("weapon pill"),
+                    ZIndex::Local(202),
+                ));
+            }
+        });
+    }
+}
+
+fn pill_bundle(
+    static_sprite_assets: &StaticSpriteAssets,
+    translation: Vec3,
+    linvel: Vec2,
+    lifetime_secs: f32,
+) -> PillBundle {
+    let timer_despawn = PillDespawnTimer(Timer::new(
+        Duration::from_secs_f32(lifetime_secs),
+        TimerMode::Repeating,
+    ));
+
+    let timer_trail_spawn = PillTrailSpawnTimer(Timer::new(
+        Duration::from_millis(TRAIL_TIMER_SPAWN_MILLIS),
+        TimerMode::Repeating,
+    ));
+
+    PillBundle {
+        sprite_bundle: SpriteBundle {
+            texture: static_sprite_assets.pill.clone(),
+            transform: Transform {
+                translation,
+                scale: Vec3::ONE * 0.5,
+                ..default()
+            },
+            ..default()
+        },
+        pill: Pill,
+        active_events: ActiveEvents::COLLISION_EVENTS,
+        timer_despawn,
+        timer_trail_spawn,
+        collider_bundle: ColliderBundle {
+            collider: Collider::cuboid(10., 5.),
+            rigid_body: RigidBody::Dynamic,
+            friction: Friction {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            density: ColliderMassProperties::Density(105.0),
+            rotation_constraints: LockedAxes::ROTATION_LOCKED_X,
+            velocity: Velocity {
+                linvel,
+                angvel: 0.0,
+            },
+            ..default()
+        },
+    }
+}
+
 fn handle_pill_throw(
     q_enemies: Query<(Entity, &Enemy)>,
     mut q_pill: Query<(Entity, &Parent, &RotatingPill, &mut PillTimer)>,
@@ -297,11 +360,20 @@ fn handle_trail_timers(
 // Physics
 // -------
 
+/// Health the player loses when hit by a pill.
+const PILL_DAMAGE: u16 = 1;
+/// The player's controls are reversed for a while after swallowing a pill.
+const PILL_EFFECT: StatusEffectHit = StatusEffectHit {
+    kind: StatusEffectKind::Confusion,
+    duration: 2.0,
+};
+
 pub fn handle_pill_player_collisions(
     mut collision_events: EventReader<CollisionEvent>,
     q_players: Query<(Entity, &Player)>,
-    q_arrows: Query<(Entity, &Pill)>,
+    q_arrows: Query<(Entity, &GlobalTransform), With<Pill>>,
     mut ev_player_hit: EventWriter<PlayerHitEvent>,
+    mut ew_status_effect: EventWriter<ApplyStatusEffectEvent>,
 ) {
     for event in collision_events.read() {
         // println!("collision event: {:?}", event);
@@ -323,8 +395,19 @@ pub fn handle_pill_player_collisions(
                 false => contact_2_player.unwrap().0,
             };
 
+            let pill_transform = match contact_1_pill.is_ok() {
+                true => contact_1_pill.unwrap().1,
+                false => contact_2_pill.unwrap().1,
+            };
+
             ev_player_hit.send(PlayerHitEvent {
                 entity: player_entity,
+                damage: PILL_DAMAGE,
+                source: pill_transform.translation().truncate(),
+            });
+            ew_status_effect.send(ApplyStatusEffectEvent {
+                entity: player_entity,
+                effect: PILL_EFFECT,
             });
         }
     }
@@ -339,6 +422,10 @@ pub struct WeaponPillPlugin;
 impl Plugin for WeaponPillPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PillTimer>()
+            .add_systems(
+                StartRun,
+                reset_resource::<PillTimer>.in_set(StartRunSet::Reset),
+            )
             // Event Handlers
             .add_systems(
                 Update,
@@ -347,12 +434,14 @@ impl Plugin for WeaponPillPlugin {
                     rotate_rotating_pills,
                     handle_pill_throw,
                     handle_pill_throw_event,
+                    handle_pill_barrage_event,
                     handle_arrow_timers,
                     handle_trail_timers,
                     handle_pill_player_collisions,
                 )
                     .run_if(in_state(GameState::GamePlay)),
             )
-            .add_event::<PillThrowEvent>();
+            .add_event::<PillThrowEvent>()
+            .add_event::<PillBarrageEvent>();
     }
 }
@@ -1,17 +1,22 @@
 use std::f32::consts::FRAC_PI_2;
 use std::time::Duration;
 
+use crate::controls::{ControlEvent, ControlSet};
 use crate::entities::characters::enemy::{Enemy, EnemyHitEvent};
+use crate::entities::health::{Dead, Health};
 use crate::entities::player::Player;
+use crate::entities::status_effects::StatusEffects;
+use crate::input_map::{InputMap, Targeting};
 use crate::physics::ColliderBundle;
 use crate::{loading::StaticSpriteAssets, GameState};
 
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use bevy_magic_light_2d::SpriteCamera;
 use bevy_rapier2d::geometry::Collider;
 use bevy_rapier2d::prelude::*;
 
+use super::definition::WeaponKind;
+use super::inventory::{tick_weapon_cooldowns, WeaponInventory};
+
 // ----------
 // Components
 // ----------
@@ -20,7 +25,10 @@ use bevy_rapier2d::prelude::*;
 pub struct Speargun;
 
 #[derive(Component, Clone, Copy, Default)]
-pub struct SpeargunArrow;
+pub struct SpeargunArrow {
+    /// Enemies the arrow can still hit, it breaks on the last one.
+    pub hits_left: u32,
+}
 
 #[derive(Component, Clone, Copy, Default)]
 pub struct SpeargunArrowTrail;
@@ -40,7 +48,6 @@ pub struct SpeargunShootEvent {}
 pub struct SpeargunBundle {
     pub sprite_bundle: SpriteBundle,
     pub speargun: Speargun,
-    pub timer_activation: SpeargunTimer,
 }
 
 #[derive(Clone, Default, Bundle)]
@@ -51,6 +58,7 @@ pub struct SpeargunArrowBundle {
     pub timer_trail_spawn: SpeargunTrailSpawnTimer,
     pub collider_bundle: ColliderBundle,
     pub active_events: ActiveEvents,
+    pub sensor: Sensor,
 }
 
 #[derive(Clone, Default, Bundle)]
@@ -64,9 +72,6 @@ pub struct SpeargunArrowTrailBundle {
 // Resources
 // ---------
 
-#[derive(Resource, Default, Clone, Component)]
-pub struct SpeargunTimer(pub Timer);
-
 #[derive(Resource, Default, Clone, Component)]
 pub struct SpeargunTrailSpawnTimer(pub Timer);
 
@@ -85,14 +90,14 @@ fn inject_speargun_sprite(
     q_players: Query<(Entity, &Parent, &Transform, &Player)>,
     mut q_spearguns: ParamSet<(Query<(&mut Transform, &Speargun), Without<Player>>,)>,
     static_sprite_assets: Res<StaticSpriteAssets>,
+    inventory: Res<WeaponInventory>,
 ) {
+    if !inventory.has(WeaponKind::Speargun) {
+        return;
+    }
+
     for (entity, _parent, _player_transform, _) in q_players.iter() {
         if q_spearguns.p0().iter().count() == 0 {
-            let timer_activation = SpeargunTimer(Timer::new(
-                Duration::from_secs_f32(1.0),
-                TimerMode::Repeating,
-            ));
-
             commands.entity(entity).with_children(|parent| {
                 parent.spawn((
                     SpeargunBundle {
@@ -103,7 +108,6 @@ fn inject_speargun_sprite(
                             ..default()
                         },
                         speargun: Speargun,
-                        timer_activation: timer_activation.clone(),
                     },
                     Name::new("weapon speargun"),
                     ZIndex::Global(303),
@@ -121,64 +125,82 @@ fn handle_speargun_attack_event(
     q_spearguns: Query<(&mut Transform, &Speargun), Without<Player>>,
     mut ev_arrow_attack: EventReader<SpeargunShootEvent>,
     static_sprite_assets: Res<StaticSpriteAssets>,
+    inventory: Res<WeaponInventory>,
 ) {
+    let Some(speargun) = inventory.slot(WeaponKind::Speargun) else {
+        return;
+    };
+    let projectile = speargun
+        .kind
+        .definition()
+        .projectile
+        .as_ref()
+        .expect("the speargun shoots arrows");
+    let collider = projectile.collider * speargun.area_multiplier;
+
     for _ in ev_arrow_attack.read() {
         for (speargun_transform, _) in q_spearguns.iter() {
             for (_, parent, player_transform, _) in q_players.iter() {
+                let z_rot = speargun_transform.rotation.to_euler(EulerRot::ZYX).0;
+
                 commands.entity(parent.get()).with_children(|parent| {
-                    let timer_despawn = SpeargunArrowDespawnTimer(Timer::new(
-                        Duration::from_secs_f32(1.0),
-                        TimerMode::Repeating,
-                    ));
-
-                    let timer_trail_spawn = SpeargunTrailSpawnTimer(Timer::new(
-                        Duration::from_millis(TRAIL_TIMER_SPAWN_MILLIS),
-                        TimerMode::Repeating,
-                    ));
-
-                    let z_rot = speargun_transform.rotation.to_euler(EulerRot::ZYX).0;
-                    let translation = player_transform.translation
-                        + 32.0 * Vec3::new(z_rot.cos(), z_rot.sin(), 0.0);
-                    let arrow_velocity = 350.0;
-
-                    parent.spawn((
-                        SpeargunArrowBundle {
-                            sprite_bundle: SpriteBundle {
-                                texture: static_sprite_assets.speargun_arrow.clone(),
-                                transform: Transform {
-                                    translation,
-                                    rotation: speargun_transform.rotation,
+                    for shot in 0..projectile.count {
+                        // fanned out evenly on both sides of the aim
+                        let offset = shot as f32 - (projectile.count - 1) as f32 / 2.0;
+                        let angle = z_rot + offset * projectile.spread;
+                        let direction = Vec2::new(angle.cos(), angle.sin());
+
+                        let timer_despawn = SpeargunArrowDespawnTimer(Timer::new(
+                            Duration::from_secs_f32(projectile.lifetime),
+                            TimerMode::Repeating,
+                        ));
+
+                        let timer_trail_spawn = SpeargunTrailSpawnTimer(Timer::new(
+                            Duration::from_millis(TRAIL_TIMER_SPAWN_MILLIS),
+                            TimerMode::Repeating,
+                        ));
+
+                        let translation =
+                            player_transform.translation + 32.0 * direction.extend(0.0);
+
+                        parent.spawn((
+                            SpeargunArrowBundle {
+                                sprite_bundle: SpriteBundle {
+                                    texture: static_sprite_assets.speargun_arrow.clone(),
+                                    transform: Transform {
+                                        translation,
+                                        rotation: Quat::from_rotation_z(angle),
+                                        scale: Vec3::splat(speargun.area_multiplier),
+                                    },
                                     ..default()
                                 },
-                                ..default()
-                            },
-                            speargun_arrow: SpeargunArrow,
-                            active_events: ActiveEvents::COLLISION_EVENTS,
-                            timer_despawn,
-                            timer_trail_spawn,
-                            collider_bundle: ColliderBundle {
-                                collider: Collider::cuboid(20., 5.),
-                                rigid_body: RigidBody::Dynamic,
-                                friction: Friction {
-                                    coefficient: 0.0,
-                                    combine_rule: CoefficientCombineRule::Min,
+                                speargun_arrow: SpeargunArrow {
+                                    hits_left: projectile.pierce + 1,
                                 },
-                                density: ColliderMassProperties::Density(105.0),
-                                rotation_constraints: LockedAxes::ROTATION_LOCKED_X,
-                                velocity: Velocity {
-                                    linvel: arrow_velocity
-                                        * Vec2 {
-                                            x: z_rot.cos(),
-                                            y: z_rot.sin(),
-                                        },
-                                    angvel: 0.0,
+                                active_events: ActiveEvents::COLLISION_EVENTS,
+                                sensor: Sensor,
+                                timer_despawn,
+                                timer_trail_spawn,
+                                collider_bundle: ColliderBundle {
+                                    collider: Collider::cuboid(collider.x, collider.y),
+                                    rigid_body: RigidBody::Dynamic,
+                                    friction: Friction {
+                                        coefficient: 0.0,
+                                        combine_rule: CoefficientCombineRule::Min,
+                                    },
+                                    density: ColliderMassProperties::Density(105.0),
+                                    rotation_constraints: LockedAxes::ROTATION_LOCKED_X,
+                                    velocity: Velocity {
+                                        linvel: projectile.speed * direction,
+                                        angvel: 0.0,
+                                    },
+                                    ..default()
                                 },
-                                ..default()
                             },
-                        },
-                        Name::new("weapon speargun arrow"),
-                        ZIndex::Local(202),
-                    ));
+                            Name::new("weapon speargun arrow"),
+                            ZIndex::Local(202),
+                        ));
+                    }
                 });
             }
         }
@@ -186,15 +208,16 @@ fn handle_speargun_attack_event(
 }
 
 fn handle_speargun_attack(
-    mut q_speargun: Query<(Entity, &Speargun, &mut SpeargunTimer)>,
+    inventory: Res<WeaponInventory>,
+    q_player: Query<&StatusEffects, With<Player>>,
     mut ev_arrow_attack: EventWriter<SpeargunShootEvent>,
-    time: Res<Time>,
 ) {
-    for (_, _, mut timer) in q_speargun.iter_mut() {
-        timer.0.tick(time.delta());
-        if timer.0.just_finished() {
-            ev_arrow_attack.send(SpeargunShootEvent {});
-        }
+    if q_player.iter().any(|effects| effects.is_stunned()) {
+        return;
+    }
+
+    if inventory.attacks(WeaponKind::Speargun) {
+        ev_arrow_attack.send(SpeargunShootEvent {});
     }
 }
 
@@ -270,120 +293,147 @@ fn handle_trail_timers(
 // Physics
 // -------
 
+/// Arrows pass through enemies, each hit uses up one of [`SpeargunArrow::hits_left`].
 pub fn handle_arrow_enemy_collisions(
+    mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
-    q_enemies: Query<(Entity, &Enemy)>,
-    q_arrows: Query<(Entity, &SpeargunArrow)>,
+    q_enemies: Query<(), (With<Enemy>, Without<Dead>)>,
+    mut q_arrows: Query<&mut SpeargunArrow>,
     mut ev_enemy_hit: EventWriter<EnemyHitEvent>,
+    inventory: Res<WeaponInventory>,
 ) {
+    let Some(speargun) = inventory.slot(WeaponKind::Speargun) else {
+        return;
+    };
+
     for event in collision_events.read() {
-        // println!("collision event: {:?}", event);
-        if let CollisionEvent::Started(e1, e2, _) = event {
-            let contact_1_enemy = q_enemies.get(*e1);
-            let contact_2_enemy = q_enemies.get(*e2);
-            let is_enemy_contact = contact_2_enemy.is_ok() || contact_1_enemy.is_ok();
-
-            let contact_1_arrow = q_arrows.get(*e1);
-            let contact_2_arrow = q_arrows.get(*e2);
-            let is_arrow_contact = contact_1_arrow.is_ok() || contact_2_arrow.is_ok();
-
-            if !(is_enemy_contact && is_arrow_contact) {
-                continue;
-            }
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+
+        let (arrow_entity, enemy_entity) = match (q_arrows.contains(*e1), q_arrows.contains(*e2)) {
+            (true, _) => (*e1, *e2),
+            (_, true) => (*e2, *e1),
+            _ => continue,
+        };
+        if !q_enemies.contains(enemy_entity) {
+            continue;
+        }
 
-            let enemy_entity = match contact_1_enemy.is_ok() {
-                true => contact_1_enemy.unwrap().0,
-                false => contact_2_enemy.unwrap().0,
-            };
+        // the arrow is only despawned at the end of the frame
+        let mut arrow = q_arrows.get_mut(arrow_entity).unwrap();
+        if arrow.hits_left == 0 {
+            continue;
+        }
 
-            ev_enemy_hit.send(EnemyHitEvent {
-                entity: enemy_entity,
-                damage: 50,
-            });
+        arrow.hits_left -= 1;
+        if arrow.hits_left == 0 {
+            commands.entity(arrow_entity).despawn_recursive();
         }
+
+        ev_enemy_hit.send(EnemyHitEvent {
+            entity: enemy_entity,
+            damage: speargun.damage(),
+            effect: speargun.kind.definition().on_hit,
+        });
     }
 }
 
-// --------
-// Controls
-// --------
+// ---------
+// Targeting
+// ---------
+
+/// Radians the aim buttons turn the speargun by in a frame.
+const AIM_TURN_STEP: f32 = 0.1;
 
 fn rotate_speargun(
-    mut q_speargun: Query<(&mut Transform, &mut Sprite, &Speargun), Without<Player>>,
+    q_speargun: &mut Query<(&mut Transform, &mut Sprite), (With<Speargun>, Without<Player>)>,
     angle: f32,
 ) {
-    for (mut transform, mut sprite, _) in q_speargun.iter_mut() {
+    for (mut transform, mut sprite) in q_speargun.iter_mut() {
         transform.rotation = Quat::from_rotation_z(angle);
 
         sprite.flip_y = angle.abs() >= FRAC_PI_2;
     }
 }
 
-// Those are exclusive systems:
-// arrows (gamepad) or mouse (keyboard)
-fn control_speargun_with_arrows(
-    input: Res<Input<KeyCode>>,
-    q_speargun: Query<(&mut Transform, &mut Sprite, &Speargun), Without<Player>>,
-) {
-    if q_speargun.iter().count() == 0 {
-        return;
-    }
+/// Enemy in range the speargun aims at in the auto-targeting modes.
+fn auto_target(
+    targeting: Targeting,
+    player_position: Vec2,
+    range: f32,
+    q_enemies: &Query<(&GlobalTransform, &Health, &Enemy), Without<Dead>>,
+) -> Option<Vec2> {
+    let in_range = q_enemies
+        .iter()
+        .filter(|(_, _, enemy)| !enemy.is_dummy)
+        .map(|(transform, health, _)| (transform.translation().truncate(), health.current))
+        .filter(|(position, _)| position.distance(player_position) <= range);
 
-    if !(input.pressed(KeyCode::Left) || input.pressed(KeyCode::Right)) {
-        return;
-    }
+    let closer = |a: Vec2, b: Vec2| {
+        b.distance_squared(player_position)
+            .total_cmp(&a.distance_squared(player_position))
+    };
 
-    let mut angle = q_speargun
-        .iter()
-        .next()
-        .unwrap()
-        .0
-        .rotation
-        .to_euler(EulerRot::ZYX)
-        .0;
-
-    if input.pressed(KeyCode::Left) {
-        angle += 0.1;
-    }
-    if input.pressed(KeyCode::Right) {
-        angle -= -0.1;
-    }
+    let target = match targeting {
+        Targeting::NearestEnemy => in_range.max_by(|(a, _), (b, _)| closer(*a, *b)),
+        Targeting::StrongestEnemy => in_range.max_by(|(a, a_health), (b, b_health)| {
+            a_health.cmp(b_health).then_with(|| closer(*a, *b))
+        }),
+        Targeting::Mouse | Targeting::Stick => None,
+    };
 
-    rotate_speargun(q_speargun, angle);
+    target.map(|(position, _)| position)
 }
 
-#[allow(unused_assignments)]
-fn control_speargun_with_mouse(
-    q_window: Query<&Window, With<PrimaryWindow>>,
-    q_camera: Query<(&Camera, &GlobalTransform), With<SpriteCamera>>,
-    q_players: Query<(Entity, &GlobalTransform, &Player)>,
-    q_speargun: Query<(&mut Transform, &mut Sprite, &Speargun), Without<Player>>,
+/// Points the speargun where the controls aim it or, in the auto-targeting modes, at an
+/// enemy in range.
+fn aim_speargun(
+    mut ev_control: EventReader<ControlEvent>,
+    input_map: Res<InputMap>,
+    inventory: Res<WeaponInventory>,
+    q_players: Query<&GlobalTransform, With<Player>>,
+    q_enemies: Query<(&GlobalTransform, &Health, &Enemy), Without<Dead>>,
+    mut q_speargun: Query<(&mut Transform, &mut Sprite), (With<Speargun>, Without<Player>)>,
 ) {
-    let (camera, camera_transform) = q_camera.single();
-    let window = q_window.single();
-
-    let mut cursor_position = Vec2::ZERO;
-    if let Some(world_position) = window
-        .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-        .map(|ray| ray.origin.truncate())
-    {
-        cursor_position = world_position;
-    } else {
+    let Some(speargun) = inventory.slot(WeaponKind::Speargun) else {
         return;
-    }
+    };
+    let Some(mut angle) = q_speargun
+        .iter()
+        .next()
+        .map(|(transform, _)| transform.rotation.to_euler(EulerRot::ZYX).0)
+    else {
+        return;
+    };
 
-    let mut player_position = Vec2::ZERO;
-    for (_, player_transform, _) in q_players.iter() {
-        player_position = player_transform.translation().truncate();
-    }
+    match input_map.targeting {
+        Targeting::Mouse | Targeting::Stick => {
+            for control in ev_control.read() {
+                if let Some(aim) = control.aim {
+                    angle = aim.y.atan2(aim.x);
+                }
+                angle += control.turn_aim * AIM_TURN_STEP;
+            }
+        }
+        targeting => {
+            ev_control.clear();
 
-    let angle = {
-        let direction = cursor_position - player_position;
-        direction.normalize().angle_between(Vec2::new(1.0, 0.0))
-    };
+            let Ok(player_transform) = q_players.get_single() else {
+                return;
+            };
+            let player_position = player_transform.translation().truncate();
+
+            if let Some(target) =
+                auto_target(targeting, player_position, speargun.range(), &q_enemies)
+            {
+                let aim = target - player_position;
+                angle = aim.y.atan2(aim.x);
+            }
+        }
+    }
 
-    rotate_speargun(q_speargun, -angle);
+    rotate_speargun(&mut q_speargun, angle);
 }
 
 // ------
@@ -394,19 +444,18 @@ pub struct WeaponSpeargunPlugin;
 
 impl Plugin for WeaponSpeargunPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SpeargunTimer>()
+        app
             // Event Handlers
             .add_systems(
                 Update,
                 (
                     inject_speargun_sprite,
-                    handle_speargun_attack,
+                    handle_speargun_attack.after(tick_weapon_cooldowns),
                     handle_speargun_attack_event,
                     handle_arrow_timers,
                     handle_trail_timers,
                     handle_arrow_enemy_collisions,
-                    control_speargun_with_arrows,
-                    control_speargun_with_mouse,
+                    aim_speargun.in_set(ControlSet::Apply),
                 )
                     .run_if(in_state(GameState::GamePlay)),
             )
@@ -7,6 +7,7 @@ use crate::entities::{
     characters::enemy::{Enemy, EnemyHitEvent},
     player::Player,
 };
+use crate::gameplay::run::{reset_resource, StartRun, StartRunSet};
 use crate::{loading::StaticSpriteAssets, GameState};
 
 // ----------
@@ -207,6 +208,7 @@ fn handle_arrow_attack(
             ev_enemy_hit.send(EnemyHitEvent {
                 entity: e,
                 damage: 88,
+                effect: None,
             });
         }
     }
@@ -221,6 +223,10 @@ pub struct WeaponArrowPlugin;
 impl Plugin for WeaponArrowPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<WeaponArrowTimer>()
+            .add_systems(
+                StartRun,
+                reset_resource::<WeaponArrowTimer>.in_set(StartRunSet::Reset),
+            )
             // Event Handlers
             .add_systems(
                 Update,
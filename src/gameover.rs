@@ -1,13 +1,50 @@
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
+use serde::Serialize;
 
-use crate::{ui::UIGameOver, AudioAssets, ButtonColors, ChangeState, FontAssets, GameState};
+use crate::{
+    gameplay::{
+        highscores::{spawn_highscore_table, Highscores, NewHighscore},
+        rng::GameRng,
+    },
+    ui::UIGameOver,
+    AudioAssets, ButtonColors, ChangeState, FontAssets, GameState,
+};
+
+/// Why a run ended, shown on the game over screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum GameEndReason {
+    PlayerDied,
+    TimeUp,
+    ObjectivesComplete,
+}
+
+impl GameEndReason {
+    pub fn text(&self) -> &'static str {
+        match self {
+            GameEndReason::PlayerDied => "ALEXTIME HAS FALLEN",
+            GameEndReason::TimeUp => "TIME IS UP",
+            GameEndReason::ObjectivesComplete => "ALL OBJECTIVES COMPLETE",
+        }
+    }
+}
 
 #[derive(Event, Clone)]
-pub struct GameOverEvent;
+pub struct GameOverEvent {
+    pub reason: GameEndReason,
+}
 
 #[derive(Event, Clone)]
-pub struct GameWinEvent;
+pub struct GameWinEvent {
+    pub reason: GameEndReason,
+}
+
+/// How the last run ended, read by the game over screen.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GameEnd {
+    pub won: bool,
+    pub reason: GameEndReason,
+}
 
 #[derive(Component)]
 struct UIGameOverButton;
@@ -16,44 +53,43 @@ struct UIGameOverButton;
 struct UIGameOverText;
 
 pub fn event_game_over(
+    mut commands: Commands,
     mut ev_game_over: EventReader<GameOverEvent>,
     mut q_ui_game_over: Query<(&mut Visibility, &UIGameOver)>,
     mut next_state: ResMut<NextState<GameState>>,
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
-    mut text_query: Query<(&mut Text, &UIGameOverText)>,
 ) {
-    for _ in ev_game_over.read() {
+    for ev in ev_game_over.read() {
         for (mut visibility, _) in q_ui_game_over.iter_mut() {
             *visibility = Visibility::Visible;
         }
 
         audio.play(audio_assets.gameover.clone()).with_volume(0.5);
         next_state.set(GameState::GameOver);
-        for (mut text_component, _) in text_query.iter_mut() {
-            text_component.sections[0].value = "  JUEGO\nTERMINADO".to_string();
-        }
+        commands.insert_resource(GameEnd {
+            won: false,
+            reason: ev.reason,
+        });
     }
 }
 
 pub fn event_game_won(
+    mut commands: Commands,
     mut ev_game_over: EventReader<GameWinEvent>,
     mut q_ui_game_over: Query<(&mut Visibility, &UIGameOver)>,
     mut next_state: ResMut<NextState<GameState>>,
-    audio: Res<Audio>,
-    audio_assets: Res<AudioAssets>,
-    mut text_query: Query<(&mut Text, &UIGameOverText)>,
 ) {
-    for _ in ev_game_over.read() {
+    for ev in ev_game_over.read() {
         for (mut visibility, _) in q_ui_game_over.iter_mut() {
             *visibility = Visibility::Visible;
         }
 
-        // audio.play(audio_assets.gameover.clone()).with_volume(0.5);
         next_state.set(GameState::GameOver);
-        for (mut text_component, _) in text_query.iter_mut() {
-            text_component.sections[0].value = "  JUEGO\nGANADO".to_string();
-        }
+        commands.insert_resource(GameEnd {
+            won: true,
+            reason: ev.reason,
+        });
     }
 }
 
@@ -63,9 +99,21 @@ pub(crate) fn despawn_ui(mut commands: Commands, query: Query<Entity, With<UIGam
     }
 }
 
-pub(crate) fn draw_ui(mut commands: Commands, font_assets: Res<FontAssets>) {
+pub(crate) fn draw_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    game_rng: Res<GameRng>,
+    highscores: Res<Highscores>,
+    new_highscore: Option<Res<NewHighscore>>,
+    game_end: Option<Res<GameEnd>>,
+) {
     print!("draw game over");
 
+    let title = match game_end.as_deref() {
+        Some(GameEnd { won: true, .. }) => "  JUEGO\nGANADO",
+        _ => "  JUEGO\nTERMINADO",
+    };
+
     // game over
     commands
         .spawn((
@@ -86,7 +134,7 @@ pub(crate) fn draw_ui(mut commands: Commands, font_assets: Res<FontAssets>) {
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "  JUEGO\nTERMINADO",
+                    title,
                     TextStyle {
                         font: font_assets.pixeloid_mono.clone(),
                         font_size: 100.0,
@@ -96,6 +144,59 @@ pub(crate) fn draw_ui(mut commands: Commands, font_assets: Res<FontAssets>) {
                 UIGameOverText,
             ));
 
+            if let Some(game_end) = game_end.as_deref() {
+                parent.spawn(
+                    TextBundle::from_section(
+                        game_end.reason.text(),
+                        TextStyle {
+                            font: font_assets.pixeloid_mono.clone(),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(220.0),
+                        ..default()
+                    }),
+                );
+            }
+
+            // seed of the run, so it can be replayed with --seed
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("SEED {}", game_rng.seed()),
+                    TextStyle {
+                        font: font_assets.pixeloid_mono.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    ..default()
+                }),
+            );
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(50.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_highscore_table(
+                        parent,
+                        &highscores,
+                        new_highscore.as_deref(),
+                        font_assets.pixeloid_mono.clone(),
+                    );
+                });
+
             parent
                 .spawn((
                     ButtonBundle {
@@ -0,0 +1,393 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use bevy_ecs_ldtk::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    entities::{
+        health::Health, level_objects::exit::LevelExitEvent, player::Player,
+        text_indicator::SpawnTextIndicatorEvent,
+    },
+    gameover::{GameEndReason, GameWinEvent},
+    ldtk::{self, LevelChangeEvent, LEVEL_1_IID},
+    loading::CampaignAssets,
+    pause::{GamePause, PausedRun},
+    GameState,
+};
+
+use super::objectives::{default_objectives, Objective, ObjectivesCompleteEvent};
+use super::progression;
+use super::run::{despawn_run_entities, reset_resource, EndRun, StartRun, StartRunSet};
+use super::waves;
+
+// ------
+// Assets
+// ------
+
+/// Levels in the order they are played.
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+impl Campaign {
+    pub fn level_index(&self, level_id: usize) -> Option<usize> {
+        self.levels
+            .iter()
+            .position(|level| level.level_id == level_id)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CampaignLevel {
+    /// LDtk `LevelID` level field, also the level's key in the wave table.
+    pub level_id: usize,
+    /// Title of the cutscene played before the level.
+    pub title: String,
+    #[serde(default)]
+    pub cutscene: Vec<DialogLine>,
+    /// All of them clear the level, clearing all waves when left out.
+    #[serde(default = "default_objectives")]
+    pub objectives: Vec<Objective>,
+    /// Seconds the level may take before the run is lost.
+    #[serde(default)]
+    pub time_limit: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DialogLine {
+    pub speaker: Speaker,
+    pub text: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speaker {
+    Alextime,
+    Gennadiy,
+}
+
+/// LDtk iid of the level with the given `LevelID` field.
+pub fn ldtk_level_iid(project: &LdtkProject, level_id: usize) -> Option<String> {
+    project
+        .iter_raw_levels()
+        .find(|level| {
+            level
+                .get_int_field("LevelID")
+                .is_ok_and(|id| *id as usize == level_id)
+        })
+        .map(|level| level.iid.clone())
+}
+
+// ------
+// Loader
+// ------
+
+#[derive(Deserialize)]
+struct CampaignDefinition {
+    levels: Vec<CampaignLevel>,
+}
+
+#[derive(Debug, Error)]
+pub enum CampaignLoaderError {
+    #[error("could not read campaign {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not parse campaign {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    #[error("{}: {reason}", path.display())]
+    Invalid { path: PathBuf, reason: String },
+}
+
+#[derive(Default)]
+pub struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Campaign, CampaignLoaderError>> {
+        Box::pin(async move {
+            let path = load_context.path().to_path_buf();
+
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(|source| CampaignLoaderError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+
+            let definition: CampaignDefinition =
+                ron::de::from_bytes(&bytes).map_err(|source| CampaignLoaderError::Parse {
+                    path: path.clone(),
+                    source,
+                })?;
+
+            if definition.levels.is_empty() {
+                return Err(CampaignLoaderError::Invalid {
+                    path,
+                    reason: "campaign has no levels".to_string(),
+                });
+            }
+
+            let mut level_ids = HashSet::new();
+            for level in definition.levels.iter() {
+                if !level_ids.insert(level.level_id) {
+                    return Err(CampaignLoaderError::Invalid {
+                        path,
+                        reason: format!("level {} is listed twice", level.level_id),
+                    });
+                }
+
+                if let Err(reason) = validate_objectives(level) {
+                    return Err(CampaignLoaderError::Invalid {
+                        path,
+                        reason: format!("level {}: {}", level.level_id, reason),
+                    });
+                }
+            }
+
+            Ok(Campaign {
+                levels: definition.levels,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+fn validate_objectives(level: &CampaignLevel) -> Result<(), &'static str> {
+    if level.objectives.is_empty() {
+        return Err("level has no objectives");
+    }
+    if level
+        .time_limit
+        .is_some_and(|seconds| !(seconds.is_finite() && seconds > 0.0))
+    {
+        return Err("time_limit must be a positive number of seconds");
+    }
+
+    for objective in level.objectives.iter() {
+        match objective {
+            Objective::Survive { seconds } if !(seconds.is_finite() && *seconds > 0.0) => {
+                return Err("Survive seconds must be a positive number");
+            }
+            Objective::Collect { count: 0, .. } => {
+                return Err("Collect count must be at least 1");
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// ---------
+// Resources
+// ---------
+
+/// How far the current run got through the campaign.
+#[derive(Resource, Default)]
+pub struct CampaignProgress {
+    /// Index into [`Campaign::levels`].
+    pub level_index: usize,
+    /// Set once the objectives of the level are complete, opens the level exits.
+    pub level_cleared: bool,
+    /// Health the player left the previous level with.
+    pub player_health: Option<u16>,
+}
+
+// -------
+// Systems
+// -------
+
+/// Selects the LDtk level of the current campaign level and starts its waves.
+pub fn enter_current_level(
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+    ldtk_projects: Res<Assets<LdtkProject>>,
+    progress: Res<CampaignProgress>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut ew_level_change: EventWriter<LevelChangeEvent>,
+) {
+    let level_id = campaigns
+        .get(&campaign_assets.campaign)
+        .and_then(|campaign| campaign.levels.get(progress.level_index))
+        .map(|level| level.level_id)
+        .unwrap_or_else(|| {
+            warn!("campaign has no level {}", progress.level_index);
+            1
+        });
+
+    let iid = ldtk_projects
+        .get(&campaign_assets.ldtk)
+        .and_then(|project| ldtk_level_iid(project, level_id))
+        .unwrap_or_else(|| {
+            warn!("no LDtk level has LevelID {}", level_id);
+            LEVEL_1_IID.to_string()
+        });
+
+    *level_selection = LevelSelection::iid(iid);
+    ew_level_change.send(LevelChangeEvent { level_id });
+}
+
+fn track_level_change(
+    mut er_level_change: EventReader<LevelChangeEvent>,
+    mut progress: ResMut<CampaignProgress>,
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    for event in er_level_change.read() {
+        let Some(level_index) = campaigns
+            .get(&campaign_assets.campaign)
+            .and_then(|campaign| campaign.level_index(event.level_id))
+        else {
+            warn!("level {} is not part of the campaign", event.level_id);
+            continue;
+        };
+
+        progress.level_index = level_index;
+        progress.level_cleared = false;
+    }
+}
+
+fn event_objectives_complete(
+    mut er_objectives_complete: EventReader<ObjectivesCompleteEvent>,
+    mut progress: ResMut<CampaignProgress>,
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+    q_player: Query<Entity, With<Player>>,
+    mut ew_game_won: EventWriter<GameWinEvent>,
+    mut ew_spawn_text_indicator: EventWriter<SpawnTextIndicatorEvent>,
+) {
+    for _ in er_objectives_complete.read() {
+        if progress.level_cleared {
+            continue;
+        }
+
+        progress.level_cleared = true;
+
+        let has_next_level = campaigns
+            .get(&campaign_assets.campaign)
+            .is_some_and(|campaign| progress.level_index + 1 < campaign.levels.len());
+
+        if !has_next_level {
+            ew_game_won.send(GameWinEvent {
+                reason: GameEndReason::ObjectivesComplete,
+            });
+            continue;
+        }
+
+        for player in q_player.iter() {
+            ew_spawn_text_indicator.send(SpawnTextIndicatorEvent {
+                text: "EXIT OPEN".to_string(),
+                entity: player,
+            });
+        }
+    }
+}
+
+fn event_level_exit(
+    mut er_level_exit: EventReader<LevelExitEvent>,
+    mut progress: ResMut<CampaignProgress>,
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+    q_player: Query<&Health, With<Player>>,
+    mut game_pause: GamePause,
+) {
+    for event in er_level_exit.read() {
+        let has_next_level = campaigns
+            .get(&campaign_assets.campaign)
+            .is_some_and(|campaign| progress.level_index + 1 < campaign.levels.len());
+
+        if !(progress.level_cleared && has_next_level) {
+            continue;
+        }
+
+        let Ok(health) = q_player.get(event.player) else {
+            continue;
+        };
+
+        progress.player_health = Some(health.current);
+        progress.level_index += 1;
+        progress.level_cleared = false;
+
+        // the next level is loaded when its cutscene ends
+        game_pause.play_cutscene();
+        break;
+    }
+}
+
+fn carry_player_health(
+    mut progress: ResMut<CampaignProgress>,
+    mut q_player: Query<&mut Health, Added<Player>>,
+) {
+    let Some(carried_health) = progress.player_health else {
+        return;
+    };
+
+    for mut health in q_player.iter_mut() {
+        health.current = carried_health.min(health.max);
+        progress.player_health = None;
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Campaign>()
+            .init_asset_loader::<CampaignLoader>()
+            .init_resource::<CampaignProgress>()
+            .add_systems(StartRun, enter_current_level.in_set(StartRunSet::Setup))
+            // reset when the run ends, the cutscene before the next run shows the first level
+            .add_systems(EndRun, reset_resource::<CampaignProgress>)
+            .add_systems(
+                OnExit(GameState::Cutscene),
+                (
+                    ldtk::despawn_game_world,
+                    despawn_run_entities,
+                    enter_current_level,
+                    ldtk::spawn_game_world,
+                )
+                    .chain()
+                    .run_if(resource_exists::<PausedRun>()),
+            )
+            .add_systems(
+                Update,
+                (
+                    track_level_change,
+                    event_objectives_complete.after(track_level_change),
+                    event_level_exit.after(event_objectives_complete),
+                    carry_player_health.after(progression::apply_player_stats),
+                )
+                    .after(waves::event_on_level_change)
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
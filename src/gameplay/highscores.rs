@@ -0,0 +1,404 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::CliArgs,
+    config::{self, ConfigError},
+    gameplay::{rng::GameRng, scoring::Score, waves::GameplayState},
+    replay::ReplayPlayer,
+    GameState,
+};
+
+/// Name of the leaderboard file in the user config directory.
+pub const HIGHSCORES_FILE: &str = "highscores.ron";
+
+pub const MAX_HIGHSCORES: usize = 10;
+pub const MAX_NAME_LEN: usize = 10;
+const DEFAULT_NAME: &str = "PLAYER";
+
+// ----
+// Data
+// ----
+
+/// Every field has a default so entries written by older versions still load.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct HighscoreEntry {
+    pub name: String,
+    pub score: u32,
+    /// 1-based, as shown in the wave counter.
+    pub wave: usize,
+    pub time_survived_secs: f32,
+    pub seed: u64,
+    /// Unix time the run ended.
+    pub timestamp: u64,
+}
+
+impl HighscoreEntry {
+    fn row(&self, rank: usize) -> String {
+        let seconds = self.time_survived_secs as u64;
+        format!(
+            "{:>2}. {:<width$} {:>6} W{:<2} {:>2}:{:02} {}",
+            rank + 1,
+            self.name,
+            self.score,
+            self.wave,
+            seconds / 60,
+            seconds % 60,
+            format_date(self.timestamp),
+            width = MAX_NAME_LEN,
+        )
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Highscores {
+    /// Name used for the next entry, the last one typed in.
+    pub last_name: String,
+    /// Best first.
+    pub entries: Vec<HighscoreEntry>,
+}
+
+impl Highscores {
+    /// Inserts `entry` after the entries with the same or a better score, returns its rank
+    /// or `None` when it doesn't make the table.
+    pub fn insert(&mut self, entry: HighscoreEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or(self.entries.len());
+
+        if rank >= MAX_HIGHSCORES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGHSCORES);
+
+        Some(rank)
+    }
+
+    /// Fixes up a table read from disk: ordering, length, names and times.
+    fn sanitize(&mut self) {
+        for entry in self.entries.iter_mut() {
+            entry.name = sanitize_name(&entry.name);
+            if !entry.time_survived_secs.is_finite() || entry.time_survived_secs < 0.0 {
+                entry.time_survived_secs = 0.0;
+            }
+        }
+
+        self.last_name = sanitize_name(&self.last_name);
+        self.entries.sort_by(|a, b| b.score.cmp(&a.score));
+        self.entries.truncate(MAX_HIGHSCORES);
+    }
+
+    fn next_name(&self) -> String {
+        if self.last_name.is_empty() {
+            DEFAULT_NAME.to_string()
+        } else {
+            self.last_name.clone()
+        }
+    }
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| is_name_char(*c))
+        .take(MAX_NAME_LEN)
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_'
+}
+
+/// `YYYY-MM-DD` of a unix timestamp, in UTC.
+fn format_date(timestamp: u64) -> String {
+    // days to civil date, from Howard Hinnant's date algorithms
+    let z = (timestamp / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// ---------
+// Resources
+// ---------
+
+/// Rank of the entry added by the run that just ended, its name is editable on the game
+/// over screen until confirmed.
+#[derive(Resource)]
+pub struct NewHighscore {
+    pub rank: usize,
+    pub confirmed: bool,
+    /// The name is filled in from the last run until the first keypress replaces it.
+    pub edited: bool,
+}
+
+// ----------
+// Components
+// ----------
+
+#[derive(Component)]
+struct HighscoreRow(usize);
+
+// --
+// UI
+// --
+
+/// Spawns the leaderboard as a column of text rows, the run's new entry highlighted.
+pub fn spawn_highscore_table(
+    parent: &mut ChildBuilder,
+    highscores: &Highscores,
+    new_highscore: Option<&NewHighscore>,
+    font: Handle<Font>,
+) {
+    let text_style = |color| TextStyle {
+        font: font.clone(),
+        font_size: 14.0,
+        color,
+    };
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "HIGH SCORES",
+                text_style(Color::WHITE),
+            ));
+
+            if highscores.entries.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "no runs yet",
+                    text_style(Color::GRAY),
+                ));
+            }
+
+            for rank in 0..highscores.entries.len() {
+                parent.spawn((
+                    TextBundle::from_section(
+                        row_text(highscores, new_highscore, rank),
+                        text_style(row_color(new_highscore, rank)),
+                    ),
+                    HighscoreRow(rank),
+                ));
+            }
+        });
+}
+
+/// The row of the run that just ended stands out.
+fn row_color(new_highscore: Option<&NewHighscore>, rank: usize) -> Color {
+    match new_highscore {
+        Some(new_highscore) if new_highscore.rank == rank => Color::YELLOW,
+        _ => Color::WHITE,
+    }
+}
+
+fn row_text(highscores: &Highscores, new_highscore: Option<&NewHighscore>, rank: usize) -> String {
+    let row = highscores.entries[rank].row(rank);
+
+    match new_highscore {
+        Some(new_highscore) if new_highscore.rank == rank && !new_highscore.confirmed => {
+            format!("{}  < TYPE NAME, ENTER", row)
+        }
+        _ => row,
+    }
+}
+
+// -------
+// Systems
+// -------
+
+/// Highscores are only kept for runs played by a person.
+pub fn recording_highscores(
+    cli_args: Res<CliArgs>,
+    replay_player: Option<Res<ReplayPlayer>>,
+) -> bool {
+    !cli_args.headless && replay_player.is_none()
+}
+
+fn load_highscores(mut highscores: ResMut<Highscores>) {
+    match config::load::<Highscores>(HIGHSCORES_FILE) {
+        Ok(Some(loaded)) => *highscores = loaded,
+        Ok(None) => {}
+        Err(e @ ConfigError::Parse { .. }) => {
+            // keep the broken file around instead of overwriting it on the next save
+            warn!("starting with empty highscores: {}", e);
+            if let Err(e) = config::backup(HIGHSCORES_FILE) {
+                error!("{}", e);
+            }
+        }
+        Err(e) => warn!("starting with empty highscores: {}", e),
+    }
+
+    highscores.sanitize();
+}
+
+fn save_highscores(highscores: &Highscores) {
+    if let Err(e) = config::save(HIGHSCORES_FILE, highscores) {
+        error!("could not save highscores: {}", e);
+    }
+}
+
+fn record_highscore(
+    mut commands: Commands,
+    mut highscores: ResMut<Highscores>,
+    score: Res<Score>,
+    gameplay_state: Res<GameplayState>,
+    game_rng: Res<GameRng>,
+) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let entry = HighscoreEntry {
+        name: highscores.next_name(),
+        score: score.score,
+        wave: gameplay_state.wave_number.unwrap_or(0) + 1,
+        time_survived_secs: score.time_survived.as_secs_f32(),
+        seed: game_rng.seed(),
+        timestamp,
+    };
+
+    if let Some(rank) = highscores.insert(entry) {
+        commands.insert_resource(NewHighscore {
+            rank,
+            confirmed: false,
+            edited: false,
+        });
+        save_highscores(&highscores);
+    }
+}
+
+fn edit_highscore_name(
+    mut highscores: ResMut<Highscores>,
+    mut new_highscore: ResMut<NewHighscore>,
+    mut er_characters: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+) {
+    if new_highscore.confirmed {
+        er_characters.clear();
+        return;
+    }
+
+    let mut name = highscores.entries[new_highscore.rank].name.clone();
+    let mut edited = new_highscore.edited;
+
+    for event in er_characters.read() {
+        if !is_name_char(event.char) {
+            continue;
+        }
+        if !edited {
+            name.clear();
+            edited = true;
+        }
+        if name.len() < MAX_NAME_LEN {
+            name.push(event.char.to_ascii_uppercase());
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        if edited {
+            name.pop();
+        } else {
+            name.clear();
+            edited = true;
+        }
+    }
+
+    if edited && !new_highscore.edited {
+        new_highscore.edited = true;
+    }
+
+    if name != highscores.entries[new_highscore.rank].name {
+        highscores.entries[new_highscore.rank].name = name;
+    }
+
+    if keys.just_pressed(KeyCode::Return) {
+        let name = highscores.entries[new_highscore.rank].name.clone();
+        if name.is_empty() {
+            return;
+        }
+
+        highscores.last_name = name;
+        new_highscore.confirmed = true;
+        save_highscores(&highscores);
+    }
+}
+
+fn update_highscore_rows(
+    highscores: Res<Highscores>,
+    new_highscore: Option<Res<NewHighscore>>,
+    mut q_rows: Query<(&mut Text, &HighscoreRow)>,
+) {
+    let new_highscore_changed = new_highscore.as_ref().is_some_and(|n| n.is_changed());
+    if !(highscores.is_changed() || new_highscore_changed) {
+        return;
+    }
+
+    for (mut text, HighscoreRow(rank)) in q_rows.iter_mut() {
+        if *rank < highscores.entries.len() {
+            text.sections[0].value = row_text(&highscores, new_highscore.as_deref(), *rank);
+            // the table can be drawn before the new highscore resource is inserted
+            text.sections[0].style.color = row_color(new_highscore.as_deref(), *rank);
+        }
+    }
+}
+
+fn finish_highscore(mut commands: Commands, highscores: Res<Highscores>) {
+    // a name typed without confirming is kept as well
+    save_highscores(&highscores);
+    commands.remove_resource::<NewHighscore>();
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct HighscoresPlugin;
+
+impl Plugin for HighscoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Highscores>()
+            .add_systems(Startup, load_highscores)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_highscore
+                    .before(crate::gameover::draw_ui)
+                    .run_if(recording_highscores),
+            )
+            .add_systems(
+                OnExit(GameState::GameOver),
+                finish_highscore.run_if(resource_exists::<NewHighscore>()),
+            )
+            .add_systems(
+                Update,
+                (
+                    edit_highscore_name
+                        .run_if(in_state(GameState::GameOver))
+                        .run_if(resource_exists::<NewHighscore>()),
+                    update_highscore_rows.after(edit_highscore_name),
+                ),
+            );
+    }
+}
@@ -1,23 +1,82 @@
 use bevy::prelude::*;
 
+use crate::{loading::WaveAssets, GameState};
+
+pub mod campaign;
+pub mod highscores;
+pub mod navigation;
+pub mod objectives;
+pub mod progression;
+pub mod rng;
+pub mod run;
 pub mod scoring;
+pub mod spawn_pattern;
+pub mod wave_table;
 pub mod waves;
 
 pub struct GameplayPlugin;
 
 impl Plugin for GameplayPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<waves::GameplayState>()
+        app.add_plugins(campaign::CampaignPlugin)
+            .add_plugins((
+                highscores::HighscoresPlugin,
+                navigation::NavigationPlugin,
+                objectives::ObjectivesPlugin,
+                progression::ProgressionPlugin,
+                run::RunPlugin,
+            ))
+            .init_asset::<wave_table::WaveTable>()
+            .init_asset_loader::<wave_table::WaveTableLoader>()
+            .init_resource::<waves::GameplayState>()
             .init_resource::<scoring::Score>()
+            .init_resource::<scoring::Combo>()
+            .init_resource::<rng::GameRng>()
+            .configure_sets(
+                Update,
+                (
+                    rng::GameRngSet::Waves,
+                    rng::GameRngSet::SpawnEnemies,
+                    rng::GameRngSet::SpawnItems,
+                    rng::GameRngSet::FixEntities,
+                    rng::GameRngSet::EnemyBrains,
+                    rng::GameRngSet::Upgrades,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                run::StartRun,
+                (
+                    rng::reseed_game_rng,
+                    run::reset_resource::<waves::GameplayState>,
+                    run::reset_resource::<scoring::Score>,
+                    run::reset_resource::<scoring::Combo>,
+                )
+                    .in_set(run::StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    waves::event_on_level_change
+                        .in_set(rng::GameRngSet::Waves)
+                        .before(waves::handle_timers),
+                    waves::reload_wave_table,
+                )
+                    .run_if(resource_exists::<WaveAssets>()),
+            )
             .add_systems(
                 Update,
                 (
-                    waves::event_on_level_change,
-                    waves::event_wave,
+                    waves::event_wave
+                        .in_set(rng::GameRngSet::Waves)
+                        .after(waves::handle_timers),
                     waves::ui_wave_info_text,
                     scoring::ui_score_text,
-                    waves::handle_timers,
-                ),
+                    scoring::tick_time_survived,
+                    scoring::tick_combo,
+                    waves::handle_timers.in_set(rng::GameRngSet::Waves),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
             )
             // Handle game over
             .add_event::<waves::WaveEvent>();
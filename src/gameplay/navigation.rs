@@ -0,0 +1,303 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::{entities::player::Player, ldtk::Wall, GameState};
+
+use super::run::{reset_resource, StartRun, StartRunSet};
+
+/// LDtk IntGrid layer the walls are painted on.
+const COLLISIONS_LAYER: &str = "Collisions";
+
+/// Moves between neighbouring cells with their cost, diagonals cost about `sqrt(2)` times more.
+const MOVES: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), 10),
+    (IVec2::new(-1, 0), 10),
+    (IVec2::new(0, 1), 10),
+    (IVec2::new(0, -1), 10),
+    (IVec2::new(1, 1), 14),
+    (IVec2::new(1, -1), 14),
+    (IVec2::new(-1, 1), 14),
+    (IVec2::new(-1, -1), 14),
+];
+
+// ----------
+// Navigation
+// ----------
+
+/// Walls of one level, on the grid of its `Collisions` layer.
+#[derive(Debug, Clone, Default)]
+pub struct NavigationGrid {
+    pub width: i32,
+    pub height: i32,
+    pub cell_size: f32,
+    walls: Vec<bool>,
+}
+
+impl NavigationGrid {
+    pub fn new(width: i32, height: i32, cell_size: f32) -> Self {
+        NavigationGrid {
+            width,
+            height,
+            cell_size,
+            walls: vec![false; (width * height).max(0) as usize],
+        }
+    }
+
+    pub fn set_wall(&mut self, cell: IVec2) {
+        if let Some(index) = self.index(cell) {
+            self.walls[index] = true;
+        }
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let inside = cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height;
+        inside.then(|| (cell.y * self.width + cell.x) as usize)
+    }
+
+    /// Cell of a position in level coordinates.
+    pub fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn is_wall(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|index| self.walls[index])
+    }
+
+    /// Whether the straight line between two positions crosses no wall.
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        // sample every half cell so the line can't skip over a wall corner
+        let steps = (from.distance(to) * 2.0 / self.cell_size).ceil().max(1.0) as usize;
+
+        (0..=steps).all(|step| {
+            let position = from.lerp(to, step as f32 / steps as f32);
+            !self.is_wall(self.cell(position))
+        })
+    }
+}
+
+/// Direction to walk in from every cell to reach the target cell the shortest way.
+#[derive(Debug, Clone, Default)]
+pub struct FlowField {
+    pub target: IVec2,
+    directions: Vec<Option<Vec2>>,
+}
+
+impl FlowField {
+    pub fn new(grid: &NavigationGrid, target: IVec2) -> Self {
+        let mut costs = vec![u32::MAX; grid.walls.len()];
+        let mut directions = vec![None; grid.walls.len()];
+
+        let Some(target_index) = grid.index(target) else {
+            return FlowField { target, directions };
+        };
+
+        // Dijkstra from the target outwards, every cell points back at the cell it was reached from
+        let mut queue = BinaryHeap::new();
+        costs[target_index] = 0;
+        directions[target_index] = Some(Vec2::ZERO);
+        queue.push(Reverse((0, target.x, target.y)));
+
+        while let Some(Reverse((cost, x, y))) = queue.pop() {
+            let cell = IVec2::new(x, y);
+            if cost > costs[grid.index(cell).unwrap()] {
+                continue;
+            }
+
+            for (step, step_cost) in MOVES {
+                let next = cell + step;
+                let Some(next_index) = grid.index(next) else {
+                    continue;
+                };
+
+                // no cutting corners, enemies would get stuck on them
+                let cuts_corner = grid.is_wall(cell + IVec2::new(step.x, 0))
+                    || grid.is_wall(cell + IVec2::new(0, step.y));
+                if grid.walls[next_index] || cuts_corner {
+                    continue;
+                }
+
+                let next_cost = cost + step_cost;
+                if next_cost < costs[next_index] {
+                    costs[next_index] = next_cost;
+                    directions[next_index] = Some(-step.as_vec2().normalize());
+                    queue.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        FlowField { target, directions }
+    }
+
+    /// `None` when the target can't be reached from `cell`.
+    pub fn direction(&self, grid: &NavigationGrid, cell: IVec2) -> Option<Vec2> {
+        grid.index(cell).and_then(|index| self.directions[index])
+    }
+}
+
+// ---------
+// Resources
+// ---------
+
+/// Navigation grids of the spawned levels and the flow field towards the player.
+#[derive(Resource, Default)]
+pub struct Navigation {
+    grids: HashMap<Entity, NavigationGrid>,
+    /// Level the player is in.
+    level: Option<Entity>,
+    flow_field: Option<FlowField>,
+}
+
+impl Navigation {
+    /// Direction to walk in from `position` to reach the player, straight at them unless a
+    /// wall is in the way.
+    pub fn direction_to_player(&self, position: Vec2, player_position: Vec2) -> Vec2 {
+        let straight = (player_position - position).normalize_or_zero();
+
+        let Some(grid) = self.level.and_then(|level| self.grids.get(&level)) else {
+            return straight;
+        };
+        if grid.line_of_sight(position, player_position) {
+            return straight;
+        }
+
+        self.flow_field
+            .as_ref()
+            .and_then(|flow_field| flow_field.direction(grid, grid.cell(position)))
+            .filter(|direction| *direction != Vec2::ZERO)
+            .unwrap_or(straight)
+    }
+}
+
+// -------
+// Systems
+// -------
+
+pub fn build_navigation_grids(
+    q_added_walls: Query<&Parent, Added<Wall>>,
+    q_walls: Query<(&GridCoords, &Parent), With<Wall>>,
+    q_parents: Query<&Parent, Without<Wall>>,
+    q_levels: Query<&LevelIid>,
+    ldtk_projects: Query<&Handle<LdtkProject>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    mut navigation: ResMut<Navigation>,
+) {
+    // walls are children of their layer, the level is the grandparent
+    let level_of = |layer: &Parent| q_parents.get(layer.get()).ok().map(|level| level.get());
+
+    let changed_levels = q_added_walls
+        .iter()
+        .filter_map(level_of)
+        .collect::<HashSet<_>>();
+
+    if changed_levels.is_empty() {
+        return;
+    }
+
+    let Some(ldtk_project) = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle))
+    else {
+        return;
+    };
+
+    for level_entity in changed_levels {
+        let Ok(level_iid) = q_levels.get(level_entity) else {
+            continue;
+        };
+        let Some(layer) = ldtk_project
+            .as_standalone()
+            .get_loaded_level_by_iid(&level_iid.to_string())
+            .and_then(|level| {
+                level
+                    .layer_instances()
+                    .iter()
+                    .find(|layer| layer.identifier == COLLISIONS_LAYER)
+            })
+        else {
+            warn!("level {} has no {} layer", level_iid, COLLISIONS_LAYER);
+            continue;
+        };
+
+        let mut grid = NavigationGrid::new(layer.c_wid, layer.c_hei, layer.grid_size as f32);
+
+        for (grid_coords, _) in q_walls
+            .iter()
+            .filter(|(_, layer)| level_of(layer) == Some(level_entity))
+        {
+            grid.set_wall(IVec2::new(grid_coords.x, grid_coords.y));
+        }
+
+        navigation.grids.insert(level_entity, grid);
+
+        if navigation.level == Some(level_entity) {
+            navigation.flow_field = None;
+        }
+    }
+}
+
+pub fn update_flow_field(
+    q_player: Query<(&Transform, &Parent), With<Player>>,
+    q_parents: Query<&Parent, Without<Player>>,
+    q_levels: Query<(), With<LevelIid>>,
+    mut navigation: ResMut<Navigation>,
+) {
+    navigation
+        .grids
+        .retain(|level_entity, _| q_levels.contains(*level_entity));
+
+    let Ok((player_transform, layer)) = q_player.get_single() else {
+        return;
+    };
+    let Ok(level) = q_parents.get(layer.get()).map(|level| level.get()) else {
+        return;
+    };
+
+    if navigation.level != Some(level) {
+        navigation.level = Some(level);
+        navigation.flow_field = None;
+    }
+
+    let Some(grid) = navigation.grids.get(&level) else {
+        return;
+    };
+
+    let target = grid.cell(player_transform.translation.truncate());
+    if navigation
+        .flow_field
+        .as_ref()
+        .is_some_and(|flow_field| flow_field.target == target)
+    {
+        return;
+    }
+
+    let flow_field = FlowField::new(grid, target);
+    navigation.flow_field = Some(flow_field);
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Navigation>()
+            .add_systems(
+                StartRun,
+                reset_resource::<Navigation>.in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    build_navigation_grids,
+                    update_flow_field.after(build_navigation_grids),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
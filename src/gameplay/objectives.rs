@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use serde::Deserialize;
+
+use crate::{
+    entities::{
+        characters::{boss::BossDefeatedEvent, enemy::Enemy},
+        items::item::{ItemStepOverEvent, ItemType},
+    },
+    gameover::{GameEndReason, GameOverEvent},
+    ldtk::LevelChangeEvent,
+    loading::CampaignAssets,
+    ui::UIObjectives,
+    GameState,
+};
+
+use super::campaign::Campaign;
+use super::run::{reset_resource, StartRun, StartRunSet};
+use super::waves::{self, GameplayState};
+
+// ----------
+// Objectives
+// ----------
+
+/// Something the player has to do to clear a level.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum Objective {
+    /// Stay alive for `seconds`.
+    Survive { seconds: f32 },
+    /// Spawn and kill every wave of the level.
+    ClearWaves,
+    /// Defeat every boss of the level.
+    KillBoss,
+    /// Pick up `count` items of type `item`.
+    Collect { item: ItemType, count: u32 },
+}
+
+/// Objectives of levels that don't list any.
+pub fn default_objectives() -> Vec<Objective> {
+    vec![Objective::ClearWaves]
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectiveProgress {
+    pub objective: Objective,
+    /// Items picked up for a `Collect` objective.
+    pub collected: u32,
+    pub complete: bool,
+}
+
+// ---------
+// Resources
+// ---------
+
+/// Objectives of the current level and how far along they are.
+#[derive(Resource, Default)]
+pub struct LevelObjectives {
+    pub objectives: Vec<ObjectiveProgress>,
+    /// Time spent in the level, stops while the run is frozen.
+    pub elapsed: Stopwatch,
+    /// The run is lost once the level takes longer than this.
+    pub time_limit: Option<Duration>,
+    /// Set once the objectives are complete or the time is up.
+    pub finished: bool,
+}
+
+impl LevelObjectives {
+    pub fn time_left(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.elapsed.elapsed()))
+    }
+}
+
+// ------
+// Events
+// ------
+
+/// Sent once every objective of the current level is complete.
+#[derive(Event, Clone)]
+pub struct ObjectivesCompleteEvent;
+
+// -------
+// Systems
+// -------
+
+fn load_level_objectives(
+    mut er_level_change: EventReader<LevelChangeEvent>,
+    mut objectives: ResMut<LevelObjectives>,
+    campaign_assets: Res<CampaignAssets>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    for event in er_level_change.read() {
+        let level = campaigns
+            .get(&campaign_assets.campaign)
+            .and_then(|campaign| {
+                campaign
+                    .level_index(event.level_id)
+                    .map(|index| &campaign.levels[index])
+            });
+
+        let (level_objectives, time_limit) = match level {
+            Some(level) => (level.objectives.clone(), level.time_limit),
+            None => (default_objectives(), None),
+        };
+
+        *objectives = LevelObjectives {
+            objectives: level_objectives
+                .into_iter()
+                .map(|objective| ObjectiveProgress {
+                    objective,
+                    collected: 0,
+                    complete: false,
+                })
+                .collect(),
+            time_limit: time_limit.map(Duration::from_secs_f32),
+            ..default()
+        };
+    }
+}
+
+fn count_collected_items(
+    mut er_item_step_over: EventReader<ItemStepOverEvent>,
+    mut objectives: ResMut<LevelObjectives>,
+) {
+    for event in er_item_step_over.read() {
+        for progress in objectives.objectives.iter_mut() {
+            if let Objective::Collect { item, .. } = progress.objective {
+                if item == event.item_type {
+                    progress.collected += 1;
+                }
+            }
+        }
+    }
+}
+
+fn track_boss_defeats(
+    mut er_boss_defeated: EventReader<BossDefeatedEvent>,
+    mut objectives: ResMut<LevelObjectives>,
+) {
+    for _ in er_boss_defeated.read() {
+        for progress in objectives.objectives.iter_mut() {
+            if progress.objective == Objective::KillBoss {
+                progress.complete = true;
+            }
+        }
+    }
+}
+
+/// Whether the last wave of the level is spawned and every enemy is dead.
+fn waves_cleared(gameplay_state: &GameplayState, q_enemies: &Query<&Enemy>) -> bool {
+    let Some(waves) = gameplay_state.current_level_waves() else {
+        return false;
+    };
+
+    // enemies of the last event are spawned by the time its timer runs out
+    let last_wave_spawned = gameplay_state.wave_number == Some(waves.len() - 1)
+        && gameplay_state.event_queue.is_empty()
+        && gameplay_state.wave_event_timer.finished();
+
+    last_wave_spawned && !q_enemies.iter().any(|enemy| !enemy.is_dummy)
+}
+
+fn evaluate_objectives(
+    time: Res<Time>,
+    mut objectives: ResMut<LevelObjectives>,
+    gameplay_state: Res<GameplayState>,
+    q_enemies: Query<&Enemy>,
+    mut ew_objectives_complete: EventWriter<ObjectivesCompleteEvent>,
+    mut ew_game_over: EventWriter<GameOverEvent>,
+) {
+    if objectives.finished || objectives.objectives.is_empty() {
+        return;
+    }
+
+    objectives.elapsed.tick(time.delta());
+    let elapsed = objectives.elapsed.elapsed_secs();
+    let waves_cleared = waves_cleared(&gameplay_state, &q_enemies);
+
+    for progress in objectives.objectives.iter_mut() {
+        progress.complete |= match progress.objective {
+            Objective::Survive { seconds } => elapsed >= seconds,
+            Objective::ClearWaves => waves_cleared,
+            // completed by `track_boss_defeats`
+            Objective::KillBoss => false,
+            Objective::Collect { count, .. } => progress.collected >= count,
+        };
+    }
+
+    if objectives
+        .objectives
+        .iter()
+        .all(|progress| progress.complete)
+    {
+        objectives.finished = true;
+        ew_objectives_complete.send(ObjectivesCompleteEvent);
+    } else if objectives.time_left() == Some(Duration::ZERO) {
+        objectives.finished = true;
+        ew_game_over.send(GameOverEvent {
+            reason: GameEndReason::TimeUp,
+        });
+    }
+}
+
+fn format_seconds(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn ui_objectives_text(
+    objectives: Res<LevelObjectives>,
+    mut q_text: Query<&mut Text, With<UIObjectives>>,
+) {
+    let elapsed = objectives.elapsed.elapsed_secs();
+
+    let mut lines = objectives
+        .objectives
+        .iter()
+        .map(|progress| {
+            let description = match &progress.objective {
+                Objective::Survive { seconds } => format!(
+                    "SURVIVE {}/{}",
+                    format_seconds(elapsed.min(*seconds)),
+                    format_seconds(*seconds)
+                ),
+                Objective::ClearWaves => "CLEAR ALL WAVES".to_string(),
+                Objective::KillBoss => "DEFEAT THE BOSS".to_string(),
+                Objective::Collect { item, count } => {
+                    let item = format!("{:?}", item).to_uppercase();
+                    format!(
+                        "COLLECT {} {}/{}",
+                        item,
+                        progress.collected.min(*count),
+                        count
+                    )
+                }
+            };
+
+            match progress.complete {
+                true => format!("[X] {}", description),
+                false => format!("[ ] {}", description),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(time_left) = objectives.time_left() {
+        lines.push(format!(
+            "TIME LEFT {}",
+            format_seconds(time_left.as_secs_f32())
+        ));
+    }
+
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct ObjectivesPlugin;
+
+impl Plugin for ObjectivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelObjectives>()
+            .add_event::<ObjectivesCompleteEvent>()
+            .add_systems(
+                StartRun,
+                reset_resource::<LevelObjectives>.in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    load_level_objectives.after(waves::event_on_level_change),
+                    count_collected_items.after(load_level_objectives),
+                    track_boss_defeats.after(load_level_objectives),
+                    evaluate_objectives
+                        .after(count_collected_items)
+                        .after(track_boss_defeats),
+                    ui_objectives_text.after(evaluate_objectives),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
@@ -0,0 +1,395 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    entities::{
+        health::{Dead, Health},
+        player::Player,
+        weapons::{definition::WeaponKind, inventory::WeaponInventory},
+    },
+    pause::GamePause,
+    replay::ReplayRecorder,
+    ui::{UIExperienceBar, UIPlayerLevel},
+    GameState,
+};
+
+use super::{
+    rng::{GameRng, GameRngSet},
+    run::{reset_resource, StartRun, StartRunSet},
+};
+
+/// Upgrades offered on every level-up.
+pub const UPGRADE_CHOICES: usize = 3;
+
+/// Added to the damage multiplier of a weapon.
+const DAMAGE_UPGRADE: f32 = 0.25;
+/// Multiplies the cooldown of a weapon.
+const COOLDOWN_UPGRADE: f32 = 0.85;
+/// The machete swings 0.3 seconds after its cooldown finishes, it needs to finish first.
+const MIN_COOLDOWN_SECS: f32 = 0.4;
+/// Added to the area multiplier of a weapon.
+const AREA_UPGRADE: f32 = 0.2;
+const MOVE_SPEED_UPGRADE: f32 = 10.0;
+const MAX_HEALTH_UPGRADE: u16 = 20;
+const PICKUP_RADIUS_UPGRADE: f32 = 20.0;
+
+// ---------
+// Resources
+// ---------
+
+/// Experience collected during the run.
+#[derive(Resource, Debug)]
+pub struct Experience {
+    pub level: u32,
+    /// Collected towards the next level.
+    pub xp: u32,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Experience { level: 1, xp: 0 }
+    }
+}
+
+impl Experience {
+    pub fn xp_to_next_level(&self) -> u32 {
+        5 * self.level
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.xp as f32 / self.xp_to_next_level() as f32
+    }
+
+    /// Adds `xp` and returns the number of levels gained.
+    pub fn add(&mut self, xp: u32) -> u32 {
+        self.xp += xp;
+
+        let mut levels = 0;
+        while self.xp >= self.xp_to_next_level() {
+            self.xp -= self.xp_to_next_level();
+            self.level += 1;
+            levels += 1;
+        }
+        levels
+    }
+}
+
+/// Stats of the player raised by upgrades, kept from one level of the run to the next.
+#[derive(Resource, Debug, Clone)]
+pub struct PlayerStats {
+    /// Pixels per second.
+    pub move_speed: f32,
+    pub max_health: u16,
+    /// Pixels from the player at which xp gems and items start flying to it.
+    pub pickup_radius: f32,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        PlayerStats {
+            move_speed: 100.0,
+            max_health: 100,
+            pickup_radius: 50.0,
+        }
+    }
+}
+
+/// Level-ups the player still has to pick an upgrade for, and the upgrades on offer.
+#[derive(Resource, Default, Debug)]
+pub struct LevelUpChoices {
+    pub pending: u32,
+    pub upgrades: Vec<Upgrade>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Upgrade {
+    NewWeapon(WeaponKind),
+    WeaponDamage(WeaponKind),
+    WeaponCooldown(WeaponKind),
+    WeaponArea(WeaponKind),
+    MoveSpeed,
+    MaxHealth,
+    PickupRadius,
+}
+
+impl Upgrade {
+    pub fn label(&self) -> String {
+        match self {
+            Upgrade::NewWeapon(kind) => format!("NEW WEAPON: {}", kind.definition().name),
+            Upgrade::WeaponDamage(kind) => format!(
+                "{} DAMAGE +{:.0}%",
+                kind.definition().name,
+                DAMAGE_UPGRADE * 100.0
+            ),
+            Upgrade::WeaponCooldown(kind) => format!(
+                "{} COOLDOWN -{:.0}%",
+                kind.definition().name,
+                (1.0 - COOLDOWN_UPGRADE) * 100.0
+            ),
+            Upgrade::WeaponArea(kind) => format!(
+                "{} AREA +{:.0}%",
+                kind.definition().name,
+                AREA_UPGRADE * 100.0
+            ),
+            Upgrade::MoveSpeed => format!("MOVE SPEED +{:.0}", MOVE_SPEED_UPGRADE),
+            Upgrade::MaxHealth => format!("MAX HP +{}", MAX_HEALTH_UPGRADE),
+            Upgrade::PickupRadius => format!("PICKUP RADIUS +{:.0}", PICKUP_RADIUS_UPGRADE),
+        }
+    }
+
+    /// Every upgrade that still does something, always in the same order.
+    fn available(inventory: &WeaponInventory) -> Vec<Upgrade> {
+        let mut upgrades = vec![
+            Upgrade::MoveSpeed,
+            Upgrade::MaxHealth,
+            Upgrade::PickupRadius,
+        ];
+
+        if !inventory.is_full() {
+            upgrades.extend(
+                WeaponKind::ALL
+                    .into_iter()
+                    .filter(|kind| !inventory.has(*kind))
+                    .map(Upgrade::NewWeapon),
+            );
+        }
+
+        for slot in inventory.slots.iter() {
+            upgrades.push(Upgrade::WeaponDamage(slot.kind));
+            upgrades.push(Upgrade::WeaponArea(slot.kind));
+            if slot.cooldown.duration().as_secs_f32() > MIN_COOLDOWN_SECS {
+                upgrades.push(Upgrade::WeaponCooldown(slot.kind));
+            }
+        }
+
+        upgrades
+    }
+
+    /// Up to [`UPGRADE_CHOICES`] different upgrades at random.
+    fn roll(inventory: &WeaponInventory, game_rng: &mut GameRng) -> Vec<Upgrade> {
+        Upgrade::available(inventory)
+            .choose_multiple(game_rng, UPGRADE_CHOICES)
+            .copied()
+            .collect()
+    }
+}
+
+// ------
+// Events
+// ------
+
+/// Experience collected by the player.
+#[derive(Event, Clone)]
+pub struct GainXpEvent {
+    pub amount: u32,
+}
+
+/// Picks the upgrade at `index` of the ones on offer in [`LevelUpChoices`].
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ChooseUpgradeEvent {
+    pub index: usize,
+}
+
+// --------------
+// Event Handlers
+// --------------
+
+fn event_gain_xp(
+    mut er_gain_xp: EventReader<GainXpEvent>,
+    mut experience: ResMut<Experience>,
+    mut choices: ResMut<LevelUpChoices>,
+) {
+    for ev in er_gain_xp.read() {
+        choices.pending += experience.add(ev.amount);
+    }
+}
+
+pub fn event_choose_upgrade(
+    mut er_choose_upgrade: EventReader<ChooseUpgradeEvent>,
+    mut choices: ResMut<LevelUpChoices>,
+    mut inventory: ResMut<WeaponInventory>,
+    mut stats: ResMut<PlayerStats>,
+    mut q_player: Query<&mut Health, With<Player>>,
+    mut game_rng: ResMut<GameRng>,
+    mut game_pause: GamePause,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    // only the first pick counts, the others were made on upgrades no longer on offer
+    let Some(index) = er_choose_upgrade.read().next().map(|ev| ev.index) else {
+        return;
+    };
+    er_choose_upgrade.clear();
+
+    let Some(upgrade) = choices.upgrades.get(index).copied() else {
+        return;
+    };
+
+    // recorded here so the replay has exactly the picks that were applied
+    if let Some(mut recorder) = recorder {
+        recorder.replay.upgrades.push(index);
+    }
+
+    match upgrade {
+        Upgrade::NewWeapon(kind) => {
+            inventory.equip(kind);
+        }
+        Upgrade::WeaponDamage(kind) => {
+            if let Some(slot) = inventory.slot_mut(kind) {
+                slot.damage_multiplier += DAMAGE_UPGRADE;
+            }
+        }
+        Upgrade::WeaponCooldown(kind) => {
+            if let Some(slot) = inventory.slot_mut(kind) {
+                let seconds = (slot.cooldown.duration().as_secs_f32() * COOLDOWN_UPGRADE)
+                    .max(MIN_COOLDOWN_SECS);
+                slot.cooldown.set_duration(Duration::from_secs_f32(seconds));
+            }
+        }
+        Upgrade::WeaponArea(kind) => {
+            if let Some(slot) = inventory.slot_mut(kind) {
+                slot.area_multiplier += AREA_UPGRADE;
+            }
+        }
+        Upgrade::MoveSpeed => stats.move_speed += MOVE_SPEED_UPGRADE,
+        Upgrade::MaxHealth => {
+            stats.max_health += MAX_HEALTH_UPGRADE;
+            for mut health in q_player.iter_mut() {
+                health.max = stats.max_health;
+                health.current = (health.current + MAX_HEALTH_UPGRADE).min(health.max);
+            }
+        }
+        Upgrade::PickupRadius => stats.pickup_radius += PICKUP_RADIUS_UPGRADE,
+    }
+
+    choices.pending -= 1;
+    if choices.pending > 0 {
+        choices.upgrades = Upgrade::roll(&inventory, &mut game_rng);
+    } else {
+        choices.upgrades.clear();
+        game_pause.resume();
+    }
+}
+
+// -------
+// Systems
+// -------
+
+/// Freezes the run behind the level-up screen while there is a level-up to spend.
+fn offer_level_up(
+    mut choices: ResMut<LevelUpChoices>,
+    inventory: Res<WeaponInventory>,
+    q_player: Query<(), (With<Player>, Without<Dead>)>,
+    mut game_rng: ResMut<GameRng>,
+    mut game_pause: GamePause,
+) {
+    if choices.pending == 0 || q_player.is_empty() {
+        return;
+    }
+
+    choices.upgrades = Upgrade::roll(&inventory, &mut game_rng);
+    game_pause.level_up();
+}
+
+/// Every level spawns a new player, it gets the max health of the run.
+pub fn apply_player_stats(
+    stats: Res<PlayerStats>,
+    mut q_player: Query<&mut Health, Added<Player>>,
+) {
+    for mut health in q_player.iter_mut() {
+        *health = Health::new(stats.max_health);
+    }
+}
+
+fn update_experience_bar(
+    experience: Res<Experience>,
+    mut q_bar: Query<&mut Style, With<UIExperienceBar>>,
+    mut q_level: Query<&mut Text, With<UIPlayerLevel>>,
+) {
+    for mut style in q_bar.iter_mut() {
+        style.width = Val::Percent(100.0 * experience.fraction());
+    }
+
+    for mut text in q_level.iter_mut() {
+        text.sections[0].value = format!("LV {}", experience.level);
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Experience>()
+            .init_resource::<PlayerStats>()
+            .init_resource::<LevelUpChoices>()
+            .add_event::<GainXpEvent>()
+            .add_event::<ChooseUpgradeEvent>()
+            .add_systems(
+                StartRun,
+                (
+                    reset_resource::<Experience>,
+                    reset_resource::<PlayerStats>,
+                    reset_resource::<LevelUpChoices>,
+                )
+                    .in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (
+                    event_gain_xp,
+                    offer_level_up
+                        .after(event_gain_xp)
+                        .in_set(GameRngSet::Upgrades),
+                    apply_player_stats,
+                    update_experience_bar
+                        .after(event_gain_xp)
+                        .run_if(resource_changed::<Experience>()),
+                )
+                    .run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(
+                Update,
+                event_choose_upgrade
+                    .in_set(GameRngSet::Upgrades)
+                    .run_if(in_state(GameState::LevelUp)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_gain_carries_over_several_levels() {
+        let mut experience = Experience::default();
+
+        // 5 to reach level 2, 10 more for level 3, 3 left over
+        assert_eq!(experience.add(18), 2);
+        assert_eq!(experience.level, 3);
+        assert_eq!(experience.xp, 3);
+    }
+
+    #[test]
+    fn cooldown_upgrade_stops_at_the_minimum_cooldown() {
+        let mut inventory = WeaponInventory::default();
+        let has_cooldown_upgrade = |inventory: &WeaponInventory| {
+            Upgrade::available(inventory).contains(&Upgrade::WeaponCooldown(WeaponKind::Machete))
+        };
+
+        let slot = inventory.slot_mut(WeaponKind::Machete).unwrap();
+        slot.cooldown
+            .set_duration(Duration::from_secs_f32(MIN_COOLDOWN_SECS + 0.1));
+        assert!(has_cooldown_upgrade(&inventory));
+
+        let slot = inventory.slot_mut(WeaponKind::Machete).unwrap();
+        slot.cooldown
+            .set_duration(Duration::from_secs_f32(MIN_COOLDOWN_SECS));
+        assert!(!has_cooldown_upgrade(&inventory));
+    }
+}
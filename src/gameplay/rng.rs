@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::cli::CliArgs;
+
+// ---------
+// Resources
+// ---------
+
+/// Source of all gameplay randomness. Reseeded every time a run starts, so the same seed
+/// replays the same waves, spawn positions and enemy behaviour.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng::new(0)
+    }
+}
+
+// -------
+// RngCore
+// -------
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+// -----------
+// System Sets
+// -----------
+
+/// Every system drawing from [`GameRng`] runs in one of these sets, which are chained, so
+/// the draws happen in the same order every frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameRngSet {
+    Waves,
+    SpawnEnemies,
+    SpawnItems,
+    FixEntities,
+    EnemyBrains,
+    Upgrades,
+}
+
+// -------
+// Helpers
+// -------
+
+/// Rng for code that has no access to [`GameRng`], such as `LdtkEntity` impls, derived from
+/// a stable key like the LDtk entity iid.
+pub fn rng_from_key(key: &str) -> ChaCha8Rng {
+    // FNV-1a, std's hashers are not guaranteed to be stable across releases
+    let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    ChaCha8Rng::seed_from_u64(hash)
+}
+
+// -------
+// Systems
+// -------
+
+pub fn reseed_game_rng(mut game_rng: ResMut<GameRng>, cli_args: Res<CliArgs>) {
+    let seed = cli_args
+        .seed
+        .unwrap_or_else(|| rand::thread_rng().next_u64());
+
+    info!("game seed: {}", seed);
+    *game_rng = GameRng::new(seed);
+}
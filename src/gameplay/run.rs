@@ -0,0 +1,81 @@
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+
+use crate::{pause::starting_or_ending_run, GameState};
+
+// ---------
+// Schedules
+// ---------
+
+/// Runs when a new run starts: entering `GamePlay` from anywhere but a frozen run, so the
+/// first run of a session and every restart go through the same setup.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StartRun;
+
+/// Runs when a run ends: leaving `GamePlay` or the pause menu, unless the run is only frozen.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EndRun;
+
+/// Sets of [`StartRun`], chained: every gameplay resource is back to its initial value
+/// before the world and UI are spawned.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StartRunSet {
+    Reset,
+    Setup,
+}
+
+// ----------
+// Components
+// ----------
+
+/// Root entities spawned during a run outside of the LDtk world, despawned when it ends.
+#[derive(Component, Default)]
+pub struct RunEntity;
+
+// -------
+// Systems
+// -------
+
+/// Puts a resource back to its default, registered in [`StartRunSet::Reset`] for every
+/// resource that carries state from one run to the next.
+pub fn reset_resource<R: Resource + Default>(mut resource: ResMut<R>) {
+    *resource = R::default();
+}
+
+pub fn start_run(world: &mut World) {
+    world.run_schedule(StartRun);
+}
+
+pub fn end_run(world: &mut World) {
+    world.run_schedule(EndRun);
+}
+
+pub fn despawn_run_entities(
+    mut commands: Commands,
+    q_run_entities: Query<Entity, With<RunEntity>>,
+) {
+    for entity in q_run_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct RunPlugin;
+
+impl Plugin for RunPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(StartRun, (StartRunSet::Reset, StartRunSet::Setup).chain())
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                start_run.run_if(starting_or_ending_run),
+            )
+            .add_systems(
+                OnExit(GameState::GamePlay),
+                end_run.run_if(starting_or_ending_run),
+            )
+            .add_systems(EndRun, despawn_run_entities);
+    }
+}
@@ -1,18 +1,87 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::ui::UIHighscore;
 
+/// A kill within this time of the previous one extends the combo.
+pub const COMBO_WINDOW_SECS: f32 = 2.0;
+/// Kills needed for each step of the multiplier.
+pub const COMBO_KILLS_PER_STEP: u32 = 5;
+pub const MAX_COMBO_MULTIPLIER: u32 = 8;
+
 #[derive(Resource, Default)]
 pub struct Score {
     pub score: u32,
+    pub time_survived: Duration,
+}
+
+/// Kills chained without taking damage, each within [`COMBO_WINDOW_SECS`] of the last.
+#[derive(Resource)]
+pub struct Combo {
+    pub kills: u32,
+    pub timer: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Combo {
+            kills: 0,
+            timer: Timer::from_seconds(COMBO_WINDOW_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.kills / COMBO_KILLS_PER_STEP).min(MAX_COMBO_MULTIPLIER)
+    }
+
+    /// Counts a kill and returns the points it is worth with the combo multiplier.
+    pub fn add_kill(&mut self, points: u32) -> u32 {
+        self.kills += 1;
+        self.timer.reset();
+
+        points * self.multiplier()
+    }
+
+    /// Ends the combo, returns whether there was one going.
+    pub fn reset(&mut self) -> bool {
+        let was_active = self.kills > 1;
+        *self = Combo::default();
+
+        was_active
+    }
+}
+
+pub fn tick_combo(mut combo: ResMut<Combo>, time: Res<Time>) {
+    if combo.kills == 0 {
+        return;
+    }
+
+    combo.timer.tick(time.delta());
+    if combo.timer.just_finished() {
+        combo.reset();
+    }
+}
+
+pub fn tick_time_survived(mut score: ResMut<Score>, time: Res<Time>) {
+    score.time_survived += time.delta();
 }
 
-pub fn ui_score_text(mut text_query: Query<(&mut Text, &UIHighscore)>, score: Res<Score>) {
-    if !score.is_changed() {
+pub fn ui_score_text(
+    mut text_query: Query<(&mut Text, &UIHighscore)>,
+    score: Res<Score>,
+    combo: Res<Combo>,
+) {
+    if !(score.is_changed() || combo.is_changed()) {
         return;
     }
 
     for (mut text, _tag) in text_query.iter_mut() {
-        text.sections[0].value = format!("SCORE: {}", score.score);
+        text.sections[0].value = match combo.multiplier() {
+            1 => format!("SCORE: {}", score.score),
+            multiplier => format!("SCORE: {} x{}", score.score, multiplier),
+        };
     }
 }
@@ -0,0 +1,249 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::entities::{
+    level_objects::spawn_point::{spawn_point_positions, SpawnPoint},
+    player::Player,
+};
+
+/// Distance kept between spawned entities and the level border.
+pub const SPAWN_MARGIN: f32 = 24.0;
+
+const MAX_SPAWN_ATTEMPTS: usize = 100;
+/// Farthest [`SpawnPattern::AwayFromPlayer`] spawns from the player.
+const AWAY_FROM_PLAYER_MAX_RADIUS: f32 = 1000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum LevelSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum SpawnPattern {
+    /// Random positions at least the spawner's safe distance away from the player, half the
+    /// level size for enemies and a third of it for pickups.
+    AwayFromPlayer,
+    /// Random positions between `min_radius` and `max_radius` pixels away from the player.
+    RingAroundPlayer { min_radius: f32, max_radius: f32 },
+    /// Random positions along the border of the level.
+    LevelEdge,
+    /// Random positions within `radius` pixels of the LDtk `SpawnPoint` called `point`.
+    Cluster { point: String, radius: f32 },
+    /// Evenly spaced positions along one side of the level, so the crowd sweeps across it.
+    LineSweep { from: LevelSide },
+}
+
+impl Default for SpawnPattern {
+    fn default() -> Self {
+        SpawnPattern::AwayFromPlayer
+    }
+}
+
+/// Level geometry a [`SpawnPattern`] is resolved against, in level coordinates.
+pub struct SpawnArea {
+    pub level_size: Vec2,
+    pub player_position: Vec2,
+    /// Closest [`SpawnPattern::AwayFromPlayer`] spawns to the player.
+    pub safe_distance: f32,
+    pub spawn_points: Vec<(String, Vec2)>,
+}
+
+impl SpawnArea {
+    pub fn positions(&self, pattern: &SpawnPattern, count: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+        (0..count)
+            .map(|index| self.position(pattern, index, count, rng))
+            .collect()
+    }
+
+    fn position(
+        &self,
+        pattern: &SpawnPattern,
+        index: usize,
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Vec2 {
+        match pattern {
+            SpawnPattern::AwayFromPlayer => {
+                self.ring_position(self.safe_distance, AWAY_FROM_PLAYER_MAX_RADIUS, rng)
+            }
+            SpawnPattern::RingAroundPlayer {
+                min_radius,
+                max_radius,
+            } => self.ring_position(*min_radius, *max_radius, rng),
+            SpawnPattern::LevelEdge => {
+                let side = match rng.gen_range(0..4) {
+                    0 => LevelSide::Left,
+                    1 => LevelSide::Right,
+                    2 => LevelSide::Top,
+                    _ => LevelSide::Bottom,
+                };
+
+                self.point_on_side(side, rng.gen_range(0.0..=1.0))
+            }
+            SpawnPattern::Cluster { point, radius } => {
+                let Some((_, center)) = self.spawn_points.iter().find(|(name, _)| name == point)
+                else {
+                    warn!("spawn point {} not found in level", point);
+                    return self.position(&SpawnPattern::default(), index, count, rng);
+                };
+
+                let r = radius * rng.gen_range(0.0_f32..=1.0).sqrt();
+                let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
+
+                self.clamp(*center + r * Vec2::new(angle.sin(), angle.cos()))
+            }
+            SpawnPattern::LineSweep { from } => {
+                self.point_on_side(*from, (index as f32 + 0.5) / count as f32)
+            }
+        }
+    }
+
+    /// A point in the level between `min_radius` and `max_radius` pixels away from the player.
+    fn ring_position(&self, min_radius: f32, max_radius: f32, rng: &mut impl Rng) -> Vec2 {
+        let mut position = self.player_position;
+
+        for _ in 0..MAX_SPAWN_ATTEMPTS {
+            let r = rng.gen_range(min_radius..=max_radius.max(min_radius));
+            let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
+
+            position = self.player_position + r * Vec2::new(angle.sin(), angle.cos());
+            if self.contains(position) {
+                break;
+            }
+        }
+
+        self.clamp(position)
+    }
+
+    /// A point on `side` of the level, `t` is the position along the side from 0 to 1.
+    fn point_on_side(&self, side: LevelSide, t: f32) -> Vec2 {
+        let min = Vec2::splat(SPAWN_MARGIN);
+        let max = self.level_size - SPAWN_MARGIN;
+
+        let along = min.lerp(max, t);
+
+        match side {
+            LevelSide::Left => Vec2::new(min.x, along.y),
+            LevelSide::Right => Vec2::new(max.x, along.y),
+            LevelSide::Top => Vec2::new(along.x, max.y),
+            LevelSide::Bottom => Vec2::new(along.x, min.y),
+        }
+    }
+
+    fn contains(&self, position: Vec2) -> bool {
+        position.x >= SPAWN_MARGIN
+            && position.x <= self.level_size.x - SPAWN_MARGIN
+            && position.y >= SPAWN_MARGIN
+            && position.y <= self.level_size.y - SPAWN_MARGIN
+    }
+
+    fn clamp(&self, position: Vec2) -> Vec2 {
+        position.clamp(
+            Vec2::splat(SPAWN_MARGIN),
+            self.level_size - Vec2::splat(SPAWN_MARGIN),
+        )
+    }
+}
+
+// ------------
+// System Param
+// ------------
+
+/// A [`SpawnArea`] around the player and where the entities spawned in it go.
+pub struct PlayerSpawnArea {
+    /// Entity layer of the player's level, spawned entities become its children.
+    pub layer: Entity,
+    /// Depth of the player, spawned entities are drawn at the same one.
+    pub z: f32,
+    pub area: SpawnArea,
+}
+
+/// Looks up the level the player is in, for spawners that place entities around it.
+#[derive(SystemParam)]
+pub struct SpawnAreaParam<'w, 's> {
+    q_player: Query<'w, 's, (&'static Transform, &'static Parent), With<Player>>,
+    q_parents: Query<'w, 's, &'static Parent, Without<Player>>,
+    q_levels: Query<'w, 's, &'static LevelIid>,
+    q_spawn_points: Query<'w, 's, (&'static Transform, &'static SpawnPoint)>,
+    ldtk_projects: Query<'w, 's, &'static Handle<LdtkProject>>,
+    ldtk_project_assets: Res<'w, Assets<LdtkProject>>,
+}
+
+impl SpawnAreaParam<'_, '_> {
+    /// `None` while the player or its level isn't spawned. `safe_fraction` of the larger
+    /// level dimension is kept from the player by [`SpawnPattern::AwayFromPlayer`].
+    pub fn get(&self, safe_fraction: f32) -> Option<PlayerSpawnArea> {
+        let (player_transform, layer) = self.q_player.get_single().ok()?;
+        // the player is a child of the entity layer, which is a child of the level
+        let level_iid = self
+            .q_parents
+            .get(layer.get())
+            .ok()
+            .and_then(|level| self.q_levels.get(level.get()).ok())?;
+        let project = self
+            .ldtk_projects
+            .get_single()
+            .ok()
+            .and_then(|handle| self.ldtk_project_assets.get(handle))?;
+        let level = project.get_raw_level_by_iid(level_iid.get())?;
+
+        Some(PlayerSpawnArea {
+            layer: layer.get(),
+            z: player_transform.translation.z,
+            area: SpawnArea {
+                level_size: Vec2::new(level.px_wid as f32, level.px_hei as f32),
+                player_position: player_transform.translation.truncate(),
+                safe_distance: level.px_wid.max(level.px_hei) as f32 * safe_fraction,
+                spawn_points: spawn_point_positions(&self.q_spawn_points),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn spawn_area(spawn_points: Vec<(String, Vec2)>) -> SpawnArea {
+        SpawnArea {
+            level_size: Vec2::splat(1024.0),
+            player_position: Vec2::splat(512.0),
+            safe_distance: 256.0,
+            spawn_points,
+        }
+    }
+
+    #[test]
+    fn away_from_player_keeps_the_safe_distance() {
+        let area = spawn_area(Vec::new());
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for position in area.positions(&SpawnPattern::default(), 100, &mut rng) {
+            assert!(position.distance(area.player_position) >= area.safe_distance - 1e-3);
+        }
+    }
+
+    #[test]
+    fn cluster_spawns_around_its_spawn_point() {
+        let gate = Vec2::new(100.0, 400.0);
+        let area = spawn_area(vec![("gate".to_string(), gate)]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let pattern = SpawnPattern::Cluster {
+            point: "gate".to_string(),
+            radius: 32.0,
+        };
+
+        for position in area.positions(&pattern, 100, &mut rng) {
+            assert!(position.distance(gate) <= 32.0 + 1e-3);
+        }
+    }
+}
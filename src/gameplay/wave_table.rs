@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::spawn_pattern::SpawnPattern;
+use super::waves::{Wave, WaveEntry};
+
+// ------
+// Assets
+// ------
+
+/// Waves for every level, keyed by the LDtk `LevelID` level field.
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct WaveTable {
+    pub levels: HashMap<usize, Vec<Wave>>,
+}
+
+impl WaveTable {
+    pub fn level_waves(&self, level_id: usize) -> Option<&Vec<Wave>> {
+        self.levels.get(&level_id)
+    }
+}
+
+// ----------------
+// File definitions
+// ----------------
+
+#[derive(Deserialize)]
+struct WaveTableDefinition {
+    levels: HashMap<usize, Vec<WaveDefinition>>,
+}
+
+#[derive(Deserialize)]
+struct WaveDefinition {
+    events: Vec<WaveEntry>,
+    /// Seconds between two events of the wave.
+    event_duration: f32,
+    /// Seconds before the next wave starts.
+    wave_duration: f32,
+}
+
+// ------
+// Loader
+// ------
+
+#[derive(Debug, Error)]
+pub enum WaveTableLoaderError {
+    #[error("could not read wave table {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not parse wave table {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    #[error("{}: level {level_id}, wave {wave_index}: {reason}", path.display())]
+    InvalidWave {
+        path: PathBuf,
+        level_id: usize,
+        wave_index: usize,
+        reason: String,
+    },
+    #[error(
+        "{}: level {level_id}, wave {wave_index}, entry {entry_index} ({entry:?}): {reason}",
+        path.display()
+    )]
+    InvalidEntry {
+        path: PathBuf,
+        level_id: usize,
+        wave_index: usize,
+        entry_index: usize,
+        entry: WaveEntry,
+        reason: String,
+    },
+}
+
+#[derive(Default)]
+pub struct WaveTableLoader;
+
+impl AssetLoader for WaveTableLoader {
+    type Asset = WaveTable;
+    type Settings = ();
+    type Error = WaveTableLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<WaveTable, WaveTableLoaderError>> {
+        Box::pin(async move {
+            let path = load_context.path().to_path_buf();
+
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(|source| WaveTableLoaderError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+
+            let definition: WaveTableDefinition =
+                ron::de::from_bytes(&bytes).map_err(|source| WaveTableLoaderError::Parse {
+                    path: path.clone(),
+                    source,
+                })?;
+
+            let mut levels = HashMap::new();
+            for (level_id, wave_definitions) in definition.levels {
+                let waves = wave_definitions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(wave_index, wave)| validate_wave(&path, level_id, wave_index, wave))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                levels.insert(level_id, waves);
+            }
+
+            Ok(WaveTable { levels })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
+
+fn validate_wave(
+    path: &Path,
+    level_id: usize,
+    wave_index: usize,
+    wave: WaveDefinition,
+) -> Result<Wave, WaveTableLoaderError> {
+    let invalid_wave = |reason: &str| WaveTableLoaderError::InvalidWave {
+        path: path.to_path_buf(),
+        level_id,
+        wave_index,
+        reason: reason.to_string(),
+    };
+
+    if wave.events.is_empty() {
+        return Err(invalid_wave("wave has no events"));
+    }
+    if !(wave.event_duration.is_finite() && wave.event_duration > 0.0) {
+        return Err(invalid_wave(
+            "event_duration must be a positive number of seconds",
+        ));
+    }
+    if !(wave.wave_duration.is_finite() && wave.wave_duration > 0.0) {
+        return Err(invalid_wave(
+            "wave_duration must be a positive number of seconds",
+        ));
+    }
+
+    for (entry_index, entry) in wave.events.iter().enumerate() {
+        let invalid_entry = |reason: &str| WaveTableLoaderError::InvalidEntry {
+            path: path.to_path_buf(),
+            level_id,
+            wave_index,
+            entry_index,
+            entry: entry.clone(),
+            reason: reason.to_string(),
+        };
+
+        if entry.count() == 0 {
+            return Err(invalid_entry("count must be at least 1"));
+        }
+        validate_pattern(entry.pattern()).map_err(invalid_entry)?;
+    }
+
+    Ok(Wave {
+        events: wave.events,
+        event_duration: Duration::from_secs_f32(wave.event_duration),
+        wave_duration: Duration::from_secs_f32(wave.wave_duration),
+    })
+}
+
+/// Rejects radii that would make the spawner panic or place nothing.
+fn validate_pattern(pattern: &SpawnPattern) -> Result<(), &'static str> {
+    match pattern {
+        SpawnPattern::RingAroundPlayer {
+            min_radius,
+            max_radius,
+        } => {
+            if !(min_radius.is_finite() && max_radius.is_finite()) {
+                return Err("ring radii must be finite numbers");
+            }
+            if *min_radius < 0.0 {
+                return Err("min_radius must not be negative");
+            }
+            if min_radius > max_radius {
+                return Err("min_radius must not be larger than max_radius");
+            }
+        }
+        SpawnPattern::Cluster { radius, .. } if !(radius.is_finite() && *radius >= 0.0) => {
+            return Err("cluster radius must be a finite number that is not negative");
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(min_radius: f32, max_radius: f32) -> SpawnPattern {
+        SpawnPattern::RingAroundPlayer {
+            min_radius,
+            max_radius,
+        }
+    }
+
+    #[test]
+    fn accepts_valid_patterns() {
+        assert!(validate_pattern(&SpawnPattern::default()).is_ok());
+        assert!(validate_pattern(&ring(0.0, 0.0)).is_ok());
+        assert!(validate_pattern(&ring(60.0, 120.0)).is_ok());
+    }
+
+    #[test]
+    fn rejects_rings_the_spawner_cannot_sample() {
+        assert!(validate_pattern(&ring(120.0, 60.0)).is_err());
+        assert!(validate_pattern(&ring(-1.0, 60.0)).is_err());
+        assert!(validate_pattern(&ring(f32::NAN, 60.0)).is_err());
+        assert!(validate_pattern(&ring(60.0, f32::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_cluster_radius() {
+        let cluster = |radius| SpawnPattern::Cluster {
+            point: "gate".to_string(),
+            radius,
+        };
+
+        assert!(validate_pattern(&cluster(32.0)).is_ok());
+        assert!(validate_pattern(&cluster(-32.0)).is_err());
+        assert!(validate_pattern(&cluster(f32::NAN)).is_err());
+    }
+}
@@ -2,24 +2,62 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use rand::Rng;
+use serde::Deserialize;
 
-use crate::entities::characters::enemy::{Enemy, EnemyType, SpawnEnemyEvent};
+use crate::entities::characters::enemy::{EnemyType, SpawnEnemyEvent};
 use crate::entities::items::item::ItemType;
-use crate::gameover::{GameOverEvent, GameWinEvent};
+use crate::entities::weapons::{definition::WeaponKind, inventory::SpawnWeaponPickupEvent};
 use crate::ldtk::LevelChangeEvent;
-use crate::GameState;
+use crate::loading::WaveAssets;
 use crate::{entities::items::item::SpawnItemEvent, ui::*};
 
-#[derive(Clone)]
+use super::rng::GameRng;
+use super::spawn_pattern::SpawnPattern;
+use super::wave_table::WaveTable;
+
+/// Anything a wave can spawn into the level.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum SpawnType {
+    Enemy(EnemyType),
+    Item(ItemType),
+    /// An item picked at random by rarity, rolled again for every item.
+    RandomItem,
+    /// A weapon pickup.
+    Weapon(WeaponKind),
+}
+
+#[derive(Clone, Debug, Deserialize)]
 pub enum WaveEntry {
-    Mierda { count: usize },
-    Pizza { count: usize },
-    Pendejo { count: usize },
-    Biboran { count: usize },
-    Boss { count: usize },
+    Spawn {
+        spawn_type: SpawnType,
+        count: usize,
+        #[serde(default)]
+        pattern: SpawnPattern,
+    },
+    /// A single enemy fought as a boss, with phases and a health bar.
+    Boss {
+        enemy_type: EnemyType,
+        #[serde(default)]
+        pattern: SpawnPattern,
+    },
+}
+
+impl WaveEntry {
+    pub fn count(&self) -> usize {
+        match self {
+            WaveEntry::Spawn { count, .. } => *count,
+            WaveEntry::Boss { .. } => 1,
+        }
+    }
+
+    pub fn pattern(&self) -> &SpawnPattern {
+        match self {
+            WaveEntry::Spawn { pattern, .. } | WaveEntry::Boss { pattern, .. } => pattern,
+        }
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Wave {
     pub events: Vec<WaveEntry>,
     pub event_duration: Duration,
@@ -30,6 +68,7 @@ pub struct Wave {
 pub struct GameplayState {
     pub wave_number: Option<usize>,
     pub current_level_id: Option<usize>,
+    pub level_waves: Vec<Wave>,
     pub event_queue: Vec<WaveEntry>,
     pub wave_timer: Timer,
     pub wave_event_timer: Timer,
@@ -42,9 +81,9 @@ pub struct WaveEvent {
 }
 
 impl GameplayState {
-    pub fn current_level_waves(&self) -> Option<Vec<Wave>> {
+    pub fn current_level_waves(&self) -> Option<&Vec<Wave>> {
         match self.current_level_id {
-            Some(1) => Some(get_level_1_waves()),
+            Some(_) if !self.level_waves.is_empty() => Some(&self.level_waves),
             _ => None,
         }
     }
@@ -59,35 +98,20 @@ impl GameplayState {
         }
     }
 
-    pub fn select_random_wave_entry(&mut self) -> Option<WaveEntry> {
-        match self.current_wave() {
-            Some(wave) => {
-                let mut rng = rand::thread_rng();
-
-                if self.event_queue.is_empty() {
-                    return None;
-                }
-
-                let random_index = rng.gen_range(0..self.event_queue.len());
-                // remove entry with index random_index from event_queue
-                self.event_queue = self
-                    .event_queue
-                    .iter()
-                    .enumerate()
-                    .filter(|(i, _)| *i != random_index)
-                    .map(|(_, v)| v.clone())
-                    .collect();
-
-                let wave_entry = wave.events.get(random_index).unwrap();
-                Some(wave_entry.clone())
-            }
-            _ => None,
+    /// Takes a random entry out of the event queue of the current wave.
+    pub fn select_random_wave_entry(&mut self, rng: &mut impl Rng) -> Option<WaveEntry> {
+        if self.current_wave().is_none() || self.event_queue.is_empty() {
+            return None;
         }
+
+        let random_index = rng.gen_range(0..self.event_queue.len());
+        Some(self.event_queue.remove(random_index))
     }
 }
 
 pub fn handle_timers(
     mut gameplay_state: ResMut<GameplayState>,
+    mut game_rng: ResMut<GameRng>,
     mut ew_wave: EventWriter<WaveEvent>,
     time: Res<Time>,
 ) {
@@ -95,7 +119,7 @@ pub fn handle_timers(
     gameplay_state.wave_event_timer.tick(time.delta());
 
     if gameplay_state.wave_timer.just_finished() {
-        if let Some(current_level_waves) = gameplay_state.current_level_waves() {
+        if let Some(current_level_waves) = gameplay_state.current_level_waves().cloned() {
             let max_wave_number = current_level_waves.len() - 1;
             let mut current_wave_number = gameplay_state.wave_number.unwrap_or(0);
             if current_wave_number < max_wave_number {
@@ -111,7 +135,9 @@ pub fn handle_timers(
                     TimerMode::Once,
                 );
 
-                let wave_event = gameplay_state.select_random_wave_entry().unwrap();
+                let wave_event = gameplay_state
+                    .select_random_wave_entry(&mut *game_rng)
+                    .unwrap();
                 ew_wave.send(WaveEvent {
                     wave_number: current_wave_number,
                     wave_entry: wave_event,
@@ -121,7 +147,7 @@ pub fn handle_timers(
     }
 
     if gameplay_state.wave_event_timer.just_finished() {
-        let wave_event = gameplay_state.select_random_wave_entry();
+        let wave_event = gameplay_state.select_random_wave_entry(&mut *game_rng);
         if wave_event.is_none() {
             return;
         }
@@ -132,57 +158,83 @@ pub fn handle_timers(
     }
 }
 
-#[allow(clippy::single_match)]
 pub fn event_on_level_change(
     mut er_on_level_change: EventReader<LevelChangeEvent>,
     mut gameplay_state: ResMut<GameplayState>,
+    mut game_rng: ResMut<GameRng>,
+    wave_assets: Res<WaveAssets>,
+    wave_tables: Res<Assets<WaveTable>>,
 
     mut ew_wave: EventWriter<WaveEvent>,
 ) {
     for event in er_on_level_change.read() {
-        match event.level_id {
-            1 => {
-                let waves = get_level_1_waves();
+        let Some(wave_table) = wave_tables.get(&wave_assets.waves) else {
+            warn!(
+                "wave table is not loaded, level {} has no waves",
+                event.level_id
+            );
+            continue;
+        };
 
-                *gameplay_state = GameplayState {
-                    wave_number: Some(0),
-                    current_level_id: Some(1),
-                    event_queue: waves[0].events.clone(),
-                    ..default()
-                };
+        let Some(waves) = wave_table.level_waves(event.level_id) else {
+            warn!("wave table has no waves for level {}", event.level_id);
+            continue;
+        };
 
-                let wave_entry = gameplay_state.select_random_wave_entry().unwrap();
+        *gameplay_state = GameplayState {
+            wave_number: Some(0),
+            current_level_id: Some(event.level_id),
+            level_waves: waves.clone(),
+            event_queue: waves[0].events.clone(),
+            ..default()
+        };
 
-                ew_wave.send(WaveEvent {
-                    wave_number: 1,
-                    wave_entry,
-                });
+        let wave_entry = gameplay_state
+            .select_random_wave_entry(&mut *game_rng)
+            .unwrap();
 
-                gameplay_state.wave_timer = Timer::new(
-                    gameplay_state.current_wave().unwrap().wave_duration,
-                    TimerMode::Once,
-                );
-            }
-            _ => {}
-        }
+        ew_wave.send(WaveEvent {
+            wave_number: 1,
+            wave_entry,
+        });
+
+        gameplay_state.wave_timer = Timer::new(
+            gameplay_state.current_wave().unwrap().wave_duration,
+            TimerMode::Once,
+        );
     }
 }
 
-pub fn check_game_won_or_lost(
-    mut next_state: ResMut<NextState<GameState>>,
-    gameplay_state: Res<GameplayState>,
-    query: Query<&Enemy>,
-    mut ev_game_won: EventWriter<GameWinEvent>,
+/// Picks up edits of the wave table made while the game is running.
+pub fn reload_wave_table(
+    mut er_wave_table: EventReader<AssetEvent<WaveTable>>,
+    mut gameplay_state: ResMut<GameplayState>,
+    wave_assets: Res<WaveAssets>,
+    wave_tables: Res<Assets<WaveTable>>,
 ) {
-    // check if current wave is last
-    if let Some(current_wave) = gameplay_state.current_wave() {
-        if gameplay_state.wave_number.unwrap()
-            == (gameplay_state.current_level_waves().unwrap().len() - 1)
-        {
-            // check if all enemies are dead
-            if query.iter().count() == 0 {
-                ev_game_won.send(GameWinEvent);
-            }
+    for event in er_wave_table.read() {
+        if !event.is_modified(&wave_assets.waves) {
+            continue;
+        }
+
+        let Some(level_id) = gameplay_state.current_level_id else {
+            continue;
+        };
+
+        let Some(waves) = wave_tables
+            .get(&wave_assets.waves)
+            .and_then(|wave_table| wave_table.level_waves(level_id))
+        else {
+            warn!("reloaded wave table has no waves for level {}", level_id);
+            continue;
+        };
+
+        info!("reloaded waves for level {}", level_id);
+
+        gameplay_state.level_waves = waves.clone();
+        let last_wave_number = gameplay_state.level_waves.len() - 1;
+        if let Some(wave_number) = gameplay_state.wave_number {
+            gameplay_state.wave_number = Some(wave_number.min(last_wave_number));
         }
     }
 }
@@ -193,41 +245,54 @@ pub fn event_wave(
     mut gameplay_state: ResMut<GameplayState>,
     mut ev_enemy_spawn: EventWriter<SpawnEnemyEvent>,
     mut ev_item_spawn: EventWriter<SpawnItemEvent>,
+    mut ev_weapon_pickup_spawn: EventWriter<SpawnWeaponPickupEvent>,
 ) {
     for event in er_on_wave_change.read() {
-        match event.wave_entry {
-            WaveEntry::Mierda { count } => {
-                ev_enemy_spawn.send(SpawnEnemyEvent {
-                    count: count as u32,
-                    enemy_type: EnemyType::Mierda,
-                });
-            }
-            WaveEntry::Pendejo { count } => {
-                ev_enemy_spawn.send(SpawnEnemyEvent {
-                    count: count as u32,
-                    enemy_type: EnemyType::Pendejo,
-                });
-            }
-            WaveEntry::Pizza { count } => {
-                ev_item_spawn.send(SpawnItemEvent {
-                    count: count as u32,
-                    item_type: ItemType::Pizza,
-                });
-            }
-            WaveEntry::Biboran { count } => {
-                ev_item_spawn.send(SpawnItemEvent {
-                    count: count as u32,
-                    item_type: ItemType::Biboran,
-                });
-            }
-            WaveEntry::Boss { count } => {
-                ev_enemy_spawn.send(SpawnEnemyEvent {
-                    count: count as u32,
-                    enemy_type: EnemyType::Psychiatrist1,
-                });
+        match &event.wave_entry {
+            WaveEntry::Spawn {
+                spawn_type,
+                count,
+                pattern,
+            } => match *spawn_type {
+                SpawnType::Enemy(enemy_type) => {
+                    ev_enemy_spawn.send(SpawnEnemyEvent {
+                        count: *count as u32,
+                        enemy_type,
+                        pattern: pattern.clone(),
+                        boss: false,
+                    });
+                }
+                SpawnType::Item(item_type) => {
+                    ev_item_spawn.send(SpawnItemEvent {
+                        count: *count as u32,
+                        item_type: Some(item_type),
+                        pattern: pattern.clone(),
+                    });
+                }
+                SpawnType::RandomItem => {
+                    ev_item_spawn.send(SpawnItemEvent {
+                        count: *count as u32,
+                        item_type: None,
+                        pattern: pattern.clone(),
+                    });
+                }
+                SpawnType::Weapon(kind) => {
+                    ev_weapon_pickup_spawn.send(SpawnWeaponPickupEvent {
+                        count: *count as u32,
+                        kind,
+                        pattern: pattern.clone(),
+                    });
+                }
+            },
+            WaveEntry::Boss {
+                enemy_type,
+                pattern,
+            } => {
                 ev_enemy_spawn.send(SpawnEnemyEvent {
-                    count: count as u32,
-                    enemy_type: EnemyType::Psychiatrist2,
+                    count: 1,
+                    enemy_type: *enemy_type,
+                    pattern: pattern.clone(),
+                    boss: true,
                 });
             }
         }
@@ -257,42 +322,3 @@ pub fn ui_wave_info_text(
         );
     }
 }
-
-#[allow(dead_code)]
-pub fn get_level_1_waves() -> Vec<Wave> {
-    vec![
-        Wave {
-            events: vec![WaveEntry::Mierda { count: 100 }],
-            event_duration: Duration::from_secs(10),
-            wave_duration: Duration::from_secs(10),
-        },
-        Wave {
-            events: vec![
-                WaveEntry::Mierda { count: 100 },
-                WaveEntry::Pizza { count: 5 },
-                WaveEntry::Mierda { count: 100 },
-                WaveEntry::Biboran { count: 5 },
-                WaveEntry::Mierda { count: 100 },
-            ],
-            event_duration: Duration::from_secs(10),
-            wave_duration: Duration::from_secs(40),
-        },
-        Wave {
-            events: vec![
-                WaveEntry::Pendejo { count: 100 },
-                WaveEntry::Pizza { count: 3 },
-                WaveEntry::Pendejo { count: 100 },
-                WaveEntry::Pizza { count: 3 },
-                WaveEntry::Pendejo { count: 100 },
-                WaveEntry::Pizza { count: 3 },
-            ],
-            event_duration: Duration::from_secs(5),
-            wave_duration: Duration::from_secs(60),
-        },
-        Wave {
-            events: vec![WaveEntry::Boss { count: 1 }],
-            event_duration: Duration::from_secs(5),
-            wave_duration: Duration::from_secs(120),
-        },
-    ]
-}
@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use bevy::app::{AppExit, PluginGroupBuilder, ScheduleRunnerPlugin};
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::render::settings::WgpuSettings;
+use bevy::render::RenderPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use serde::Serialize;
+
+use crate::{
+    cli::CliArgs,
+    gameover::{GameEndReason, GameOverEvent, GameWinEvent},
+    gameplay::{
+        progression::{event_choose_upgrade, ChooseUpgradeEvent},
+        rng::GameRng,
+        run::{reset_resource, StartRun, StartRunSet},
+        scoring::Score,
+        waves::GameplayState,
+    },
+    replay::{ReplayPlayer, REPLAY_TIMESTEP},
+    GameState,
+};
+
+/// Bevy's default plugins without a window, winit event loop or GPU renderer. Assets, UI
+/// and sprite types stay registered so the gameplay systems run unchanged.
+pub fn headless_plugins() -> PluginGroupBuilder {
+    DefaultPlugins
+        .set(WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            close_when_requested: false,
+        })
+        .set(RenderPlugin {
+            render_creation: WgpuSettings {
+                backends: None,
+                ..default()
+            }
+            .into(),
+        })
+        .set(LogPlugin {
+            filter: "warn,legend_of_mierda=info".into(),
+            level: bevy::log::Level::INFO,
+        })
+        .disable::<WinitPlugin>()
+        .add(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+}
+
+// ---------
+// Resources
+// ---------
+
+#[derive(Resource, Default)]
+struct RunOutcome {
+    won: bool,
+    reason: Option<GameEndReason>,
+}
+
+/// Printed to stdout as a single RON line when each headless run ends.
+#[derive(Serialize, Debug)]
+struct RunSummary {
+    seed: u64,
+    won: bool,
+    reason: Option<GameEndReason>,
+    score: u32,
+    level: Option<usize>,
+    wave: Option<usize>,
+    frames: u32,
+    game_seconds: f32,
+}
+
+#[derive(Resource, Default)]
+struct RunFrames(u32);
+
+/// Runs still to play after the current one.
+#[derive(Resource)]
+struct RunsLeft(u32);
+
+// -------
+// Systems
+// -------
+
+fn count_frames(mut frames: ResMut<RunFrames>) {
+    frames.0 += 1;
+}
+
+fn track_outcome(
+    mut outcome: ResMut<RunOutcome>,
+    mut er_game_over: EventReader<GameOverEvent>,
+    mut er_game_won: EventReader<GameWinEvent>,
+) {
+    for event in er_game_over.read() {
+        outcome.won = false;
+        outcome.reason = Some(event.reason);
+    }
+    for event in er_game_won.read() {
+        outcome.won = true;
+        outcome.reason = Some(event.reason);
+    }
+}
+
+/// Nobody is there to pick an upgrade, the first one on offer is taken.
+fn choose_first_upgrade(mut ew_choose_upgrade: EventWriter<ChooseUpgradeEvent>) {
+    ew_choose_upgrade.send(ChooseUpgradeEvent { index: 0 });
+}
+
+/// Prints the summary of the run and starts the next one, or exits after the last.
+fn finish_run(
+    outcome: Res<RunOutcome>,
+    frames: Res<RunFrames>,
+    game_rng: Res<GameRng>,
+    score: Res<Score>,
+    gameplay_state: Res<GameplayState>,
+    mut runs_left: ResMut<RunsLeft>,
+    mut cli_args: ResMut<CliArgs>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ew_exit: EventWriter<AppExit>,
+) {
+    let summary = RunSummary {
+        seed: game_rng.seed(),
+        won: outcome.won,
+        reason: outcome.reason,
+        score: score.score,
+        level: gameplay_state.current_level_id,
+        wave: gameplay_state.wave_number,
+        frames: frames.0,
+        game_seconds: frames.0 as f32 * REPLAY_TIMESTEP.as_secs_f32(),
+    };
+
+    match ron::to_string(&summary) {
+        Ok(line) => println!("{}", line),
+        Err(e) => error!("could not serialize run summary: {}", e),
+    }
+
+    if runs_left.0 == 0 {
+        ew_exit.send(AppExit);
+        return;
+    }
+    runs_left.0 -= 1;
+
+    // a given seed plays the runs with the seeds after it, so the batch can be repeated
+    if let Some(seed) = cli_args.seed.as_mut() {
+        *seed = seed.wrapping_add(1);
+    }
+    next_state.set(GameState::GamePlay);
+}
+
+// ------
+// Plugin
+// ------
+
+/// Runs games without a window: starts right after loading, advances time at a fixed
+/// timestep as fast as possible and prints a summary on every game over. Exits after the
+/// number of runs asked for with `--runs`.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        let runs = app.world.resource::<CliArgs>().runs.unwrap_or(1);
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(REPLAY_TIMESTEP))
+            .init_resource::<RunOutcome>()
+            .init_resource::<RunFrames>()
+            .insert_resource(RunsLeft(runs - 1))
+            .add_systems(
+                StartRun,
+                (reset_resource::<RunOutcome>, reset_resource::<RunFrames>)
+                    .in_set(StartRunSet::Reset),
+            )
+            .add_systems(
+                Update,
+                (count_frames, track_outcome).run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(
+                Update,
+                choose_first_upgrade
+                    .before(event_choose_upgrade)
+                    .run_if(in_state(GameState::LevelUp))
+                    .run_if(not(resource_exists::<ReplayPlayer>())),
+            )
+            .add_systems(OnEnter(GameState::GameOver), finish_run);
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{config, controls::PlayerGamepad};
+
+/// Name of the bindings file in the user config directory.
+pub const INPUT_MAP_FILE: &str = "input.ron";
+
+// -------
+// Actions
+// -------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Attack,
+    AimLeft,
+    AimRight,
+    Pause,
+    Interact,
+    Debug,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 10] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Attack,
+        InputAction::AimLeft,
+        InputAction::AimRight,
+        InputAction::Pause,
+        InputAction::Interact,
+        InputAction::Debug,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "UP",
+            InputAction::MoveDown => "DOWN",
+            InputAction::MoveLeft => "LEFT",
+            InputAction::MoveRight => "RIGHT",
+            InputAction::Attack => "ATTACK",
+            InputAction::AimLeft => "AIM LEFT",
+            InputAction::AimRight => "AIM RIGHT",
+            InputAction::Pause => "PAUSE",
+            InputAction::Interact => "INTERACT",
+            InputAction::Debug => "DEBUG",
+        }
+    }
+}
+
+/// How the speargun picks the direction it shoots in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Targeting {
+    /// At the mouse cursor.
+    #[default]
+    Mouse,
+    /// With the right stick of the gamepad.
+    Stick,
+    NearestEnemy,
+    /// At the enemy with the most health left.
+    StrongestEnemy,
+}
+
+impl Targeting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Targeting::Mouse => "MOUSE",
+            Targeting::Stick => "STICK",
+            Targeting::NearestEnemy => "NEAREST",
+            Targeting::StrongestEnemy => "STRONGEST",
+        }
+    }
+
+    /// The mode after this one, the controls screen cycles through them.
+    pub fn next(&self) -> Targeting {
+        match self {
+            Targeting::Mouse => Targeting::Stick,
+            Targeting::Stick => Targeting::NearestEnemy,
+            Targeting::NearestEnemy => Targeting::StrongestEnemy,
+            Targeting::StrongestEnemy => Targeting::Mouse,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn source(&self) -> InputSource {
+        match self {
+            Binding::Key(_) => InputSource::Keyboard,
+            Binding::Gamepad(_) => InputSource::Gamepad,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Gamepad(button) => write!(f, "Pad {:?}", button),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Keyboard,
+    Gamepad,
+}
+
+// ---------
+// Resources
+// ---------
+
+#[derive(Debug, Error)]
+pub enum RebindError {
+    #[error("{binding} is already bound to {}", action.label())]
+    Conflict {
+        binding: Binding,
+        action: InputAction,
+    },
+}
+
+/// Keys and gamepad buttons bound to every [`InputAction`], at most one of each source.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    pub bindings: BTreeMap<InputAction, Vec<Binding>>,
+    /// How the speargun aims, saved along with the bindings.
+    #[serde(default)]
+    pub targeting: Targeting,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let bindings = [
+            (
+                InputAction::MoveUp,
+                KeyCode::W,
+                Some(GamepadButtonType::DPadUp),
+            ),
+            (
+                InputAction::MoveDown,
+                KeyCode::S,
+                Some(GamepadButtonType::DPadDown),
+            ),
+            (
+                InputAction::MoveLeft,
+                KeyCode::A,
+                Some(GamepadButtonType::DPadLeft),
+            ),
+            (
+                InputAction::MoveRight,
+                KeyCode::D,
+                Some(GamepadButtonType::DPadRight),
+            ),
+            (
+                InputAction::Attack,
+                KeyCode::Space,
+                Some(GamepadButtonType::South),
+            ),
+            (
+                InputAction::AimLeft,
+                KeyCode::Left,
+                Some(GamepadButtonType::LeftTrigger),
+            ),
+            (
+                InputAction::AimRight,
+                KeyCode::Right,
+                Some(GamepadButtonType::RightTrigger),
+            ),
+            (
+                InputAction::Pause,
+                KeyCode::Escape,
+                Some(GamepadButtonType::Start),
+            ),
+            (
+                InputAction::Interact,
+                KeyCode::E,
+                Some(GamepadButtonType::West),
+            ),
+            (InputAction::Debug, KeyCode::F1, None),
+        ]
+        .into_iter()
+        .map(|(action, key, button)| {
+            let mut bindings = vec![Binding::Key(key)];
+            bindings.extend(button.map(Binding::Gamepad));
+            (action, bindings)
+        })
+        .collect();
+
+        InputMap {
+            bindings,
+            targeting: Targeting::default(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: InputAction) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn action_for(&self, binding: Binding) -> Option<InputAction> {
+        self.bindings
+            .iter()
+            .find(|(_, bindings)| bindings.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+
+    /// Bindings shared by more than one action.
+    pub fn conflicts(&self) -> Vec<(Binding, InputAction, InputAction)> {
+        let mut conflicts = vec![];
+
+        for (action, bindings) in self.bindings.iter() {
+            for binding in bindings {
+                for (other_action, other_bindings) in self.bindings.range(*action..).skip(1) {
+                    if other_bindings.contains(binding) {
+                        conflicts.push((*binding, *action, *other_action));
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Replaces the binding of the same source, refusing bindings used by another action.
+    pub fn rebind(&mut self, action: InputAction, binding: Binding) -> Result<(), RebindError> {
+        match self.action_for(binding) {
+            Some(bound_action) if bound_action == action => return Ok(()),
+            Some(bound_action) => {
+                return Err(RebindError::Conflict {
+                    binding,
+                    action: bound_action,
+                })
+            }
+            None => {}
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|b| b.source() != binding.source());
+        bindings.push(binding);
+
+        Ok(())
+    }
+}
+
+// ------------
+// System Param
+// ------------
+
+/// Reads actions through the [`InputMap`] from the keyboard and the player's gamepad.
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    input_map: Res<'w, InputMap>,
+    keys: Res<'w, Input<KeyCode>>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    player_gamepad: Res<'w, PlayerGamepad>,
+}
+
+impl ActionInput<'_> {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed_on(InputSource::Keyboard, action)
+            || self.pressed_on(InputSource::Gamepad, action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed_on(InputSource::Keyboard, action)
+            || self.just_pressed_on(InputSource::Gamepad, action)
+    }
+
+    pub fn targeting(&self) -> Targeting {
+        self.input_map.targeting
+    }
+
+    pub fn pressed_on(&self, source: InputSource, action: InputAction) -> bool {
+        self.any_binding(
+            source,
+            action,
+            |keys, key| keys.pressed(key),
+            |buttons, button| buttons.pressed(button),
+        )
+    }
+
+    pub fn just_pressed_on(&self, source: InputSource, action: InputAction) -> bool {
+        self.any_binding(
+            source,
+            action,
+            |keys, key| keys.just_pressed(key),
+            |buttons, button| buttons.just_pressed(button),
+        )
+    }
+
+    fn any_binding(
+        &self,
+        source: InputSource,
+        action: InputAction,
+        key_check: impl Fn(&Input<KeyCode>, KeyCode) -> bool,
+        button_check: impl Fn(&Input<GamepadButton>, GamepadButton) -> bool,
+    ) -> bool {
+        self.input_map
+            .bindings(action)
+            .iter()
+            .filter(|binding| binding.source() == source)
+            .any(|binding| match *binding {
+                Binding::Key(key) => key_check(&self.keys, key),
+                Binding::Gamepad(button_type) => match self.player_gamepad.0 {
+                    Some(gamepad) => {
+                        button_check(&self.buttons, GamepadButton::new(gamepad, button_type))
+                    }
+                    None => false,
+                },
+            })
+    }
+}
+
+// -------
+// Systems
+// -------
+
+pub fn load_input_map(mut input_map: ResMut<InputMap>) {
+    match config::load::<InputMap>(INPUT_MAP_FILE) {
+        Ok(Some(loaded)) => *input_map = loaded,
+        Ok(None) => {}
+        Err(e) => warn!("using default key bindings: {}", e),
+    }
+
+    for (binding, action, other_action) in input_map.conflicts() {
+        warn!(
+            "{} is bound to both {} and {}",
+            binding,
+            action.label(),
+            other_action.label()
+        );
+    }
+}
+
+pub fn save_input_map(input_map: Res<InputMap>) {
+    if let Err(e) = config::save(INPUT_MAP_FILE, &*input_map) {
+        error!("could not save key bindings: {}", e);
+    }
+}
+
+/// Run condition toggled by the [`InputAction::Debug`] binding, off at start.
+pub fn debug_toggle_active(mut active: Local<bool>, input: ActionInput) -> bool {
+    if input.just_pressed(InputAction::Debug) {
+        *active = !*active;
+    }
+
+    *active
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .init_resource::<PlayerGamepad>()
+            .add_systems(PreStartup, load_input_map);
+    }
+}
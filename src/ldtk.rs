@@ -7,10 +7,14 @@ use bevy_magic_light_2d::SpriteCamera;
 use bevy_rapier2d::prelude::*;
 
 use crate::entities::{
-    characters::enemy::{create_enemy_bundle, Enemy},
-    items::item::{create_item_bundle, Item},
+    characters::enemy::Enemy,
+    items::{
+        attraction::Attractable,
+        item::{create_item_bundle, Item},
+    },
     player::Player,
 };
+use crate::loading::CampaignAssets;
 
 const ASPECT_RATIO: f32 = 1.0;
 pub const LEVEL_1_IID: &str = "d53f9950-c640-11ed-8430-4942c04951ff";
@@ -101,7 +105,10 @@ pub fn camera_fit_inside_current_level(
 
     let player_translation = player_query.single().translation();
 
-    let (mut orthographic_projection, mut camera_transform, _) = camera_query.single_mut();
+    let Ok((mut orthographic_projection, mut camera_transform, _)) = camera_query.get_single_mut()
+    else {
+        return;
+    };
 
     for (level_transform, level_iid) in &level_query {
         if let Some(ldtk_level) = project.get_raw_level_by_iid(level_iid.get()) {
@@ -337,44 +344,26 @@ pub fn fix_missing_ldtk_entities(
     asset_server: Res<AssetServer>,
     texture_atlasses: ResMut<Assets<TextureAtlas>>,
     mut commands: Commands,
-    q_enemies: Query<(Entity, &Enemy), Without<Collider>>,
     q_items: Query<(Entity, &Item), Without<Collider>>,
 ) {
     let asset_server = asset_server.into_inner();
     let texture_atlasses = texture_atlasses.into_inner();
 
-    for (e, enemy) in q_enemies.iter().filter(|(_, m)| !m.is_dummy) {
-        let bundle = create_enemy_bundle(asset_server, texture_atlasses, false, enemy.enemy_type);
+    for (e, item) in q_items.iter().filter(|(_, m)| !m.is_dummy) {
+        let bundle = create_item_bundle(asset_server, texture_atlasses, false, item.item_type);
         commands.entity(e).insert((
             bundle.collider_bundle,
-            bundle.direction_update_time,
-            bundle.animated_character_sprite,
-            bundle.character_animation,
-            bundle.animation_timer,
-            // bundle.sprite_bundle,
+            Attractable::default(),
             Visibility::Visible,
         ));
     }
-
-    for (e, item) in q_items.iter().filter(|(_, m)| !m.is_dummy) {
-        let bundle = create_item_bundle(asset_server, texture_atlasses, false, item.item_type);
-        commands
-            .entity(e)
-            .insert((bundle.collider_bundle, Visibility::Visible));
-    }
 }
 
-pub fn spawn_game_world(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut ew_level_change: EventWriter<LevelChangeEvent>,
-) {
+pub fn spawn_game_world(mut commands: Commands, campaign_assets: Res<CampaignAssets>) {
     commands.spawn(LdtkWorldBundle {
-        ldtk_handle: asset_server.load("levels/example.ldtk"),
+        ldtk_handle: campaign_assets.ldtk.clone(),
         ..Default::default()
     });
-
-    ew_level_change.send(LevelChangeEvent { level_id: 1 });
 }
 
 pub fn despawn_game_world(mut commands: Commands, level_query: Query<(Entity, &LevelSet)>) {
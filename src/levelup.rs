@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::progression::{event_choose_upgrade, ChooseUpgradeEvent, Experience, LevelUpChoices},
+    loading::FontAssets,
+    ButtonColors, GameState,
+};
+
+// ----------
+// Components
+// ----------
+
+#[derive(Component)]
+struct LevelUpScreen;
+
+/// Picks the upgrade at this index of the [`LevelUpChoices`].
+#[derive(Component, Clone, Copy)]
+struct UpgradeButton(usize);
+
+// -------
+// Systems
+// -------
+
+/// Draws the upgrades on offer, again after every pick while level-ups are left.
+fn draw_level_up_screen(
+    mut commands: Commands,
+    choices: Res<LevelUpChoices>,
+    experience: Res<Experience>,
+    q_screen: Query<Entity, With<LevelUpScreen>>,
+    font_assets: Res<FontAssets>,
+) {
+    if !choices.is_changed() && !q_screen.is_empty() {
+        return;
+    }
+
+    for entity in q_screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // levels still waiting for an upgrade were already gained
+    let level = experience.level + 1 - choices.pending;
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                z_index: ZIndex::Global(500),
+                ..default()
+            },
+            LevelUpScreen,
+            Name::new("level up screen"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("LEVEL {}", level),
+                TextStyle {
+                    font: font_assets.pixeloid_mono.clone(),
+                    font_size: 80.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for (index, upgrade) in choices.upgrades.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(600.0),
+                                height: Val::Px(50.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgba_u8(0, 0, 0, 0).into(),
+                            ..default()
+                        },
+                        ButtonColors {
+                            normal: Color::WHITE,
+                            hovered: Color::LIME_GREEN,
+                        },
+                        UpgradeButton(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            upgrade.label(),
+                            TextStyle {
+                                font: font_assets.pixeloid_mono.clone(),
+                                font_size: 30.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn cleanup_level_up_screen(mut commands: Commands, q_screen: Query<Entity, With<LevelUpScreen>>) {
+    for entity in q_screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn click_upgrade_buttons(
+    interaction_query: Query<
+        (&Interaction, &ButtonColors, &Children, &UpgradeButton),
+        Changed<Interaction>,
+    >,
+    mut q_text: Query<&mut Text>,
+    mut ew_choose_upgrade: EventWriter<ChooseUpgradeEvent>,
+) {
+    for (interaction, button_colors, children, button) in &interaction_query {
+        let color = match *interaction {
+            Interaction::Pressed => {
+                ew_choose_upgrade.send(ChooseUpgradeEvent { index: button.0 });
+                button_colors.hovered
+            }
+            Interaction::Hovered => button_colors.hovered,
+            Interaction::None => button_colors.normal,
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(*child) {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct LevelUpPlugin;
+
+impl Plugin for LevelUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::LevelUp), cleanup_level_up_screen)
+            .add_systems(
+                Update,
+                (
+                    // the last pick resumes the game, no empty screen is drawn for it
+                    draw_level_up_screen.before(event_choose_upgrade),
+                    click_upgrade_buttons.before(event_choose_upgrade),
+                )
+                    .run_if(in_state(GameState::LevelUp)),
+            );
+    }
+}
@@ -1,16 +1,27 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
+use bevy_ecs_ldtk::prelude::LdtkProject;
 use bevy_kira_audio::AudioSource;
 
-use crate::{sprites::*, GameState};
+use crate::{
+    cli::CliArgs,
+    entities::characters::{definition::EnemyDefinition, enemy::EnemyType},
+    gameplay::{campaign::Campaign, wave_table::WaveTable},
+    sprites::*,
+    GameState,
+};
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(GameState::Loading).continue_to_state(GameState::Splash),
-        );
+        // headless runs skip the splash screen, menu and cutscene
+        let next_state = match app.world.get_resource::<CliArgs>() {
+            Some(cli_args) if cli_args.headless => GameState::GamePlay,
+            _ => GameState::Splash,
+        };
+
+        app.add_loading_state(LoadingState::new(GameState::Loading).continue_to_state(next_state));
 
         app.add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading);
         app.add_collection_to_loading_state::<_, TextureAssets>(GameState::Loading);
@@ -19,6 +30,9 @@ impl Plugin for LoadingPlugin {
         app.add_collection_to_loading_state::<_, SceneAssets>(GameState::Loading);
         app.add_collection_to_loading_state::<_, AnimationAssets>(GameState::Loading);
         app.add_collection_to_loading_state::<_, StaticSpriteAssets>(GameState::Loading);
+        app.add_collection_to_loading_state::<_, WaveAssets>(GameState::Loading);
+        app.add_collection_to_loading_state::<_, CampaignAssets>(GameState::Loading);
+        app.add_collection_to_loading_state::<_, EnemyAssets>(GameState::Loading);
 
         app.init_resource::<FontAssets>();
         app.init_resource::<MaterialAssets>();
@@ -93,6 +107,43 @@ pub struct StaticSpriteAssets {
     pub pill: Handle<Image>,
 }
 
+#[derive(AssetCollection, Resource)]
+pub struct WaveAssets {
+    #[asset(path = "levels/example.waves.ron")]
+    pub waves: Handle<WaveTable>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct CampaignAssets {
+    #[asset(path = "levels/example.ldtk")]
+    pub ldtk: Handle<LdtkProject>,
+    #[asset(path = "levels/example.campaign.ron")]
+    pub campaign: Handle<Campaign>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct EnemyAssets {
+    #[asset(path = "enemies/mierda.enemy.ron")]
+    pub mierda: Handle<EnemyDefinition>,
+    #[asset(path = "enemies/pendejo.enemy.ron")]
+    pub pendejo: Handle<EnemyDefinition>,
+    #[asset(path = "enemies/psychiatrist-1.enemy.ron")]
+    pub psychiatrist_1: Handle<EnemyDefinition>,
+    #[asset(path = "enemies/psychiatrist-2.enemy.ron")]
+    pub psychiatrist_2: Handle<EnemyDefinition>,
+}
+
+impl EnemyAssets {
+    pub fn definition(&self, enemy_type: EnemyType) -> &Handle<EnemyDefinition> {
+        match enemy_type {
+            EnemyType::Mierda => &self.mierda,
+            EnemyType::Pendejo => &self.pendejo,
+            EnemyType::Psychiatrist1 => &self.psychiatrist_1,
+            EnemyType::Psychiatrist2 => &self.psychiatrist_2,
+        }
+    }
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct AvatarAssets {
     #[asset(path = "avatars/alextime.png")]
@@ -2,7 +2,7 @@
 
 use bevy::log::LogPlugin;
 use bevy::render::camera::RenderTarget;
-use bevy::{input::common_conditions::input_toggle_active, prelude::*, window::PresentMode};
+use bevy::{prelude::*, window::PresentMode};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_kira_audio::prelude::*;
@@ -14,6 +14,7 @@ use bevy_particle_systems::*;
 use bevy_rapier2d::prelude::*;
 use bevy_scene_hook::HookPlugin;
 use bevy_tweening::TweeningPlugin;
+use gameplay::run::{EndRun, StartRun, StartRunSet};
 use ldtk::LEVEL_1_IID;
 use pecs::prelude::*;
 
@@ -23,17 +24,25 @@ use menu::*;
 use postprocessing::PostProcessSettings;
 
 mod audio;
+mod cli;
+mod config;
 mod controls;
 mod cutscene;
 mod entities;
 mod gameover;
 mod gameplay;
+mod headless;
+mod input_map;
 mod ldtk;
+mod levelup;
 mod loading;
 mod menu;
 mod particles;
+mod pause;
 mod physics;
 mod postprocessing;
+mod rebinding;
+mod replay;
 mod splashscreen;
 mod sprites;
 mod ui;
@@ -46,14 +55,35 @@ enum GameState {
     Splash,
     Menu,
     Cutscene,
+    Controls,
     GamePlay,
+    Paused,
+    LevelUp,
     GameOver,
 }
 
 fn main() {
+    let cli_args = match cli::CliArgs::from_env() {
+        Ok(cli_args) => cli_args,
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    let headless = cli_args.headless;
+
     let mut app = App::new();
 
-    app.add_state::<GameState>()
+    app.add_state::<GameState>().insert_resource(cli_args);
+
+    if headless {
+        app.add_plugins((
+            headless::headless_plugins(),
+            AudioPlugin,
+            headless::HeadlessPlugin,
+        ));
+    } else {
+        app
         // .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()).set())
         .add_plugins((
             DefaultPlugins
@@ -75,12 +105,20 @@ fn main() {
                     level: bevy::log::Level::DEBUG,
                 }),
             AudioPlugin, /*PostProcessPlugin*/))
-        .add_plugins((HookPlugin, PecsPlugin, TweeningPlugin, BevyMagicLight2DPlugin))
-        .add_plugins((LoadingPlugin, MenuPlugin, CutscenePlugin, LegendOfMierdaPlugin))
-        .add_plugins(audio::InternalAudioPlugin)
+        .add_plugins(BevyMagicLight2DPlugin)
         .add_plugins(
-            WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::Escape)),
-        )
+            WorldInspectorPlugin::default().run_if(input_map::debug_toggle_active),
+        );
+    }
+
+    app.add_plugins((HookPlugin, PecsPlugin, TweeningPlugin))
+        .add_plugins((
+            LoadingPlugin,
+            MenuPlugin,
+            CutscenePlugin,
+            LegendOfMierdaPlugin,
+        ))
+        .add_plugins(audio::InternalAudioPlugin)
         .add_plugins(ParticleSystemPlugin)
         // Physics
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
@@ -148,16 +186,23 @@ impl Plugin for LegendOfMierdaPlugin {
             gameplay::GameplayPlugin,
             gameover::GameOverPlugin,
             splashscreen::SplashscreenPlugin,
+            replay::ReplayPlugin,
+            input_map::InputMapPlugin,
+            rebinding::RebindingPlugin,
+            pause::PausePlugin,
+            levelup::LevelUpPlugin,
         ))
-        .add_systems(Startup, (spawn_camera).after(setup_post_processing_camera))
         .add_systems(
-            OnEnter(GameState::GamePlay),
-            (ldtk::spawn_game_world, ui::draw_ui),
+            Startup,
+            (spawn_camera)
+                .after(setup_post_processing_camera)
+                .run_if(resource_exists::<CameraTargets>()),
         )
         .add_systems(
-            OnExit(GameState::GamePlay),
-            (ldtk::despawn_game_world, ui::despawn_ui),
+            StartRun,
+            (ldtk::spawn_game_world, ui::draw_ui).in_set(StartRunSet::Setup),
         )
+        .add_systems(EndRun, (ldtk::despawn_game_world, ui::despawn_ui))
         .add_systems(
             Update,
             (
@@ -180,11 +225,23 @@ impl Plugin for LegendOfMierdaPlugin {
         )
         // Controls
         .add_event::<controls::ControlEvent>()
+        .configure_sets(
+            Update,
+            (controls::ControlSet::Input, controls::ControlSet::Apply).chain(),
+        )
         .add_systems(
             Update,
-            (controls::keyboard_controls, controls::control_character)
+            (
+                controls::keyboard_controls.in_set(controls::ControlSet::Input),
+                // sent after the keyboard so an active gamepad wins
+                controls::gamepad_controls
+                    .in_set(controls::ControlSet::Input)
+                    .after(controls::keyboard_controls),
+                controls::control_character.in_set(controls::ControlSet::Apply),
+            )
                 .run_if(in_state(GameState::GamePlay)),
         )
+        .add_systems(Update, controls::handle_gamepad_connections)
         // Particles
         .add_systems(
             Update,
@@ -1,3 +1,4 @@
+use crate::gameplay::highscores::{spawn_highscore_table, Highscores};
 use crate::loading::FontAssets;
 
 use crate::CutsceneAssets;
@@ -41,12 +42,13 @@ impl Default for ButtonColors {
 struct Menu;
 
 #[derive(Component)]
-struct StartGameButton;
+struct MenuButtonText;
 
 fn setup_menu(
     mut commands: Commands,
     cutscene_assets: Res<CutsceneAssets>,
     font_assets: Res<FontAssets>,
+    highscores: Res<Highscores>,
 ) {
     info!("menu");
 
@@ -98,36 +100,58 @@ fn setup_menu(
             Menu,
         ))
         .with_children(|children| {
-            let button_colors = ButtonColors::default();
-            children
-                .spawn((
-                    ButtonBundle {
-                        style: Style {
-                            width: Val::Px(318.0),
-                            height: Val::Px(100.0),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
+            for (label, font_size, state) in [
+                ("START", 100.0, GameState::Cutscene),
+                ("CONTROLS", 40.0, GameState::Controls),
+            ] {
+                children
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(318.0),
+                                height: Val::Px(font_size),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            background_color: Color::rgba_u8(0, 0, 0, 0).into(),
                             ..Default::default()
                         },
-                        background_color: Color::rgba_u8(0, 0, 0, 0).into(),
-                        ..Default::default()
-                    },
-                    button_colors,
-                    ChangeState(GameState::Cutscene),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        TextBundle::from_section(
-                            "START",
-                            TextStyle {
-                                font_size: 100.0,
-                                font: font_assets.pixeloid_mono.clone(),
-                                color: Color::WHITE,
-                            },
-                        ),
-                        StartGameButton,
-                    ));
-                });
+                        ButtonColors::default(),
+                        ChangeState(state),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font_size,
+                                    font: font_assets.pixeloid_mono.clone(),
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            MenuButtonText,
+                        ));
+                    });
+            }
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            Menu,
+            Name::new("menu highscores"),
+        ))
+        .with_children(|parent| {
+            spawn_highscore_table(parent, &highscores, None, font_assets.pixeloid_mono.clone());
         });
 }
 
@@ -137,27 +161,26 @@ pub struct ChangeState(pub GameState);
 fn click_play_button(
     mut next_state: ResMut<NextState<GameState>>,
     mut interaction_query: Query<
-        (&Interaction, &ButtonColors, Option<&ChangeState>),
+        (&Interaction, &ButtonColors, Option<&ChangeState>, &Children),
         (Changed<Interaction>, With<Button>),
     >,
-    mut start_game_button: Query<(&mut Text, &StartGameButton)>,
+    mut q_button_text: Query<&mut Text, With<MenuButtonText>>,
 ) {
-    for (interaction, button_colors, change_state) in &mut interaction_query {
-        match *interaction {
+    for (interaction, button_colors, change_state, children) in &mut interaction_query {
+        let color = match *interaction {
             Interaction::Pressed => {
                 if let Some(state) = change_state {
                     next_state.set(state.0.clone());
                 }
+                continue;
             }
-            Interaction::Hovered => {
-                for (mut text, _) in start_game_button.iter_mut() {
-                    text.sections[0].style.color = button_colors.hovered;
-                }
-            }
-            Interaction::None => {
-                for (mut text, _) in start_game_button.iter_mut() {
-                    text.sections[0].style.color = button_colors.normal;
-                }
+            Interaction::Hovered => button_colors.hovered,
+            Interaction::None => button_colors.normal,
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = q_button_text.get_mut(*child) {
+                text.sections[0].style.color = color;
             }
         }
     }
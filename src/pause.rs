@@ -0,0 +1,281 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    audio::GameplayMusic,
+    gameplay::run,
+    input_map::{ActionInput, InputAction},
+    loading::FontAssets,
+    rebinding::ControlsBackState,
+    ButtonColors, GameState,
+};
+
+// ---------
+// Resources
+// ---------
+
+/// Present while a run is frozen behind the pause menu, a cutscene or a level-up, so leaving and
+/// going back to `GamePlay` doesn't start or end a run.
+#[derive(Resource)]
+pub struct PausedRun;
+
+/// Run condition for starting and ending runs on `GamePlay` transitions, false when the
+/// game is only being paused or resumed.
+pub fn starting_or_ending_run(paused_run: Option<Res<PausedRun>>) -> bool {
+    paused_run.is_none()
+}
+
+// ------------
+// System Param
+// ------------
+
+/// Freezes and unfreezes everything that keeps going outside of the `GamePlay` state:
+/// virtual time (wave, weapon and animation timers, `pecs` timeouts), Rapier and music.
+#[derive(SystemParam)]
+pub struct GamePause<'w, 's> {
+    commands: Commands<'w, 's>,
+    next_state: ResMut<'w, NextState<GameState>>,
+    time: ResMut<'w, Time<Virtual>>,
+    rapier_config: ResMut<'w, RapierConfiguration>,
+    gameplay_music: Option<Res<'w, GameplayMusic>>,
+    audio_instances: ResMut<'w, Assets<AudioInstance>>,
+}
+
+impl GamePause<'_, '_> {
+    pub fn pause(&mut self) {
+        self.commands.insert_resource(PausedRun);
+        self.freeze(true);
+        self.next_state.set(GameState::Paused);
+    }
+
+    pub fn resume(&mut self) {
+        self.freeze(false);
+        self.next_state.set(GameState::GamePlay);
+    }
+
+    /// Leaves the paused run for `state`, tearing it down like leaving `GamePlay` would.
+    pub fn end_run(&mut self, state: GameState) {
+        self.commands.remove_resource::<PausedRun>();
+        self.freeze(false);
+        self.next_state.set(state);
+    }
+
+    /// Plays a cutscene on top of the frozen run.
+    pub fn play_cutscene(&mut self) {
+        self.commands.insert_resource(PausedRun);
+        self.freeze(true);
+        self.next_state.set(GameState::Cutscene);
+    }
+
+    /// Opens the level-up screen on top of the frozen run.
+    pub fn level_up(&mut self) {
+        self.commands.insert_resource(PausedRun);
+        self.freeze(true);
+        self.next_state.set(GameState::LevelUp);
+    }
+
+    /// Opens the controls screen on top of the paused run.
+    pub fn open_settings(&mut self) {
+        self.commands
+            .insert_resource(ControlsBackState(GameState::Paused));
+        self.next_state.set(GameState::Controls);
+    }
+
+    fn freeze(&mut self, frozen: bool) {
+        if frozen {
+            self.time.pause();
+        } else {
+            self.time.unpause();
+        }
+
+        self.rapier_config.physics_pipeline_active = !frozen;
+
+        let Some(gameplay_music) = &self.gameplay_music else {
+            return;
+        };
+        if let Some(instance) = self.audio_instances.get_mut(&gameplay_music.0) {
+            if frozen {
+                instance.pause(AudioTween::default());
+            } else {
+                instance.resume(AudioTween::default());
+            }
+        }
+    }
+}
+
+// ----------
+// Components
+// ----------
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Component, Clone, Copy)]
+enum PauseMenuButton {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+// -------
+// Systems
+// -------
+
+fn handle_pause_input(input: ActionInput, state: Res<State<GameState>>, mut game_pause: GamePause) {
+    if !input.just_pressed(InputAction::Pause) {
+        return;
+    }
+
+    match state.get() {
+        GameState::GamePlay => game_pause.pause(),
+        GameState::Paused => game_pause.resume(),
+        _ => {}
+    }
+}
+
+fn clear_paused_run(mut commands: Commands) {
+    commands.remove_resource::<PausedRun>();
+}
+
+fn setup_pause_menu(mut commands: Commands, font_assets: Res<FontAssets>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                z_index: ZIndex::Global(500),
+                ..default()
+            },
+            PauseMenu,
+            Name::new("pause menu"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "PAUSA",
+                TextStyle {
+                    font: font_assets.pixeloid_mono.clone(),
+                    font_size: 100.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for (label, button) in [
+                ("RESUME", PauseMenuButton::Resume),
+                ("RESTART", PauseMenuButton::Restart),
+                ("SETTINGS", PauseMenuButton::Settings),
+                ("QUIT TO MENU", PauseMenuButton::Quit),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(400.0),
+                                height: Val::Px(50.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgba_u8(0, 0, 0, 0).into(),
+                            ..default()
+                        },
+                        ButtonColors {
+                            normal: Color::WHITE,
+                            hovered: Color::LIME_GREEN,
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: font_assets.pixeloid_mono.clone(),
+                                font_size: 40.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn cleanup_pause_menu(mut commands: Commands, menu: Query<Entity, With<PauseMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn click_pause_menu_buttons(
+    mut game_pause: GamePause,
+    interaction_query: Query<
+        (&Interaction, &ButtonColors, &Children, &PauseMenuButton),
+        Changed<Interaction>,
+    >,
+    mut q_text: Query<&mut Text>,
+) {
+    for (interaction, button_colors, children, button) in &interaction_query {
+        let color = match *interaction {
+            Interaction::Pressed => {
+                match button {
+                    PauseMenuButton::Resume => game_pause.resume(),
+                    PauseMenuButton::Restart => game_pause.end_run(GameState::GamePlay),
+                    PauseMenuButton::Settings => game_pause.open_settings(),
+                    PauseMenuButton::Quit => game_pause.end_run(GameState::Menu),
+                }
+                button_colors.hovered
+            }
+            Interaction::Hovered => button_colors.hovered,
+            Interaction::None => button_colors.normal,
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(*child) {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), setup_pause_menu)
+            .add_systems(
+                OnExit(GameState::Paused),
+                (
+                    cleanup_pause_menu,
+                    // leaving for anything but the game or its settings ends the run
+                    run::end_run.run_if(starting_or_ending_run),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_pause_input
+                        .run_if(in_state(GameState::GamePlay).or_else(in_state(GameState::Paused))),
+                    click_pause_menu_buttons.run_if(in_state(GameState::Paused)),
+                    // only right after resuming, a run paused this frame keeps its marker
+                    clear_paused_run
+                        .before(handle_pause_input)
+                        .run_if(in_state(GameState::GamePlay))
+                        .run_if(state_changed::<GameState>())
+                        .run_if(resource_exists::<PausedRun>()),
+                ),
+            );
+    }
+}
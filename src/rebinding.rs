@@ -0,0 +1,335 @@
+use bevy::prelude::*;
+
+use crate::{
+    input_map::{save_input_map, Binding, InputAction, InputMap},
+    loading::FontAssets,
+    ButtonColors, ChangeState, GameState,
+};
+
+// ----------
+// Components
+// ----------
+
+#[derive(Component)]
+struct ControlsMenu;
+
+#[derive(Component)]
+struct RebindButton(InputAction);
+
+#[derive(Component)]
+struct BindingText(InputAction);
+
+/// Cycles through the speargun [`Targeting`](crate::input_map::Targeting) modes.
+#[derive(Component)]
+struct TargetingButton;
+
+#[derive(Component)]
+struct TargetingText;
+
+#[derive(Component)]
+struct ResetBindingsButton;
+
+#[derive(Component)]
+struct RebindStatusText;
+
+// ---------
+// Resources
+// ---------
+
+/// Action waiting for a key or gamepad button, and the last rebinding result.
+#[derive(Resource, Default)]
+struct Rebinding {
+    action: Option<InputAction>,
+    message: String,
+}
+
+/// State the BACK button of the controls screen returns to.
+#[derive(Resource)]
+pub struct ControlsBackState(pub GameState);
+
+impl Default for ControlsBackState {
+    fn default() -> Self {
+        ControlsBackState(GameState::Menu)
+    }
+}
+
+// -------
+// Systems
+// -------
+
+fn setup_controls_menu(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    back_state: Res<ControlsBackState>,
+) {
+    let text_style = |font_size| TextStyle {
+        font: font_assets.pixeloid_mono.clone(),
+        font_size,
+        color: Color::WHITE,
+    };
+
+    let button_style = Style {
+        width: Val::Px(500.0),
+        height: Val::Px(36.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                ..default()
+            },
+            ControlsMenu,
+            Name::new("controls menu"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("CONTROLS", text_style(50.0)));
+
+            for action in InputAction::ALL {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: button_style.clone(),
+                            background_color: Color::rgba_u8(0, 0, 0, 0).into(),
+                            ..default()
+                        },
+                        ButtonColors {
+                            normal: Color::WHITE,
+                            hovered: Color::LIME_GREEN,
+                        },
+                        RebindButton(action),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section("", text_style(20.0)),
+                            BindingText(action),
+                        ));
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        background_color: Color::rgba_u8(0, 0, 0, 0).into(),
+                        ..default()
+                    },
+                    ButtonColors {
+                        normal: Color::WHITE,
+                        hovered: Color::LIME_GREEN,
+                    },
+                    TargetingButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("", text_style(20.0)),
+                        TargetingText,
+                    ));
+                });
+
+            parent.spawn((
+                TextBundle::from_section("", text_style(16.0)),
+                RebindStatusText,
+            ));
+
+            for (label, button) in [("RESET", None), ("BACK", Some(back_state.0.clone()))] {
+                let mut button_commands = parent.spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        background_color: Color::rgba_u8(0, 0, 0, 0).into(),
+                        ..default()
+                    },
+                    ButtonColors {
+                        normal: Color::WHITE,
+                        hovered: Color::LIME_GREEN,
+                    },
+                ));
+
+                match button {
+                    Some(state) => button_commands.insert(ChangeState(state)),
+                    None => button_commands.insert(ResetBindingsButton),
+                };
+
+                button_commands.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(label, text_style(30.0)));
+                });
+            }
+        });
+
+    commands.insert_resource(Rebinding::default());
+}
+
+fn cleanup_controls_menu(mut commands: Commands, menu: Query<Entity, With<ControlsMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.insert_resource(ControlsBackState::default());
+}
+
+fn click_controls_buttons(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut input_map: ResMut<InputMap>,
+    interaction_query: Query<
+        (
+            &Interaction,
+            &ButtonColors,
+            &Children,
+            Option<&RebindButton>,
+            Option<&TargetingButton>,
+            Option<&ChangeState>,
+            Option<&ResetBindingsButton>,
+        ),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut q_text: Query<&mut Text>,
+) {
+    for (interaction, button_colors, children, rebind, targeting, change_state, reset) in
+        &interaction_query
+    {
+        let color = match *interaction {
+            Interaction::Pressed => {
+                if let Some(RebindButton(action)) = rebind {
+                    // clicking the waiting action again cancels
+                    rebinding.action = match rebinding.action {
+                        Some(waiting) if waiting == *action => None,
+                        _ => Some(*action),
+                    };
+                    rebinding.message.clear();
+                }
+                if targeting.is_some() {
+                    input_map.targeting = input_map.targeting.next();
+                }
+                if let Some(state) = change_state {
+                    next_state.set(state.0.clone());
+                }
+                if reset.is_some() {
+                    *input_map = InputMap::default();
+                    rebinding.action = None;
+                    rebinding.message = "bindings reset to defaults".to_string();
+                }
+                button_colors.hovered
+            }
+            Interaction::Hovered => button_colors.hovered,
+            Interaction::None => button_colors.normal,
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(*child) {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut input_map: ResMut<InputMap>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+
+    let binding = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        });
+
+    let Some(binding) = binding else {
+        return;
+    };
+
+    match input_map.rebind(action, binding) {
+        Ok(()) => {
+            rebinding.action = None;
+            rebinding.message = format!("{} bound to {}", action.label(), binding);
+        }
+        Err(e) => rebinding.message = e.to_string(),
+    }
+}
+
+fn update_binding_texts(
+    rebinding: Res<Rebinding>,
+    input_map: Res<InputMap>,
+    mut q_binding_text: Query<
+        (&mut Text, &BindingText),
+        (Without<RebindStatusText>, Without<TargetingText>),
+    >,
+    mut q_targeting_text: Query<&mut Text, (With<TargetingText>, Without<RebindStatusText>)>,
+    mut q_status_text: Query<&mut Text, With<RebindStatusText>>,
+) {
+    if !(rebinding.is_changed() || input_map.is_changed()) {
+        return;
+    }
+
+    for (mut text, BindingText(action)) in q_binding_text.iter_mut() {
+        let bindings = if rebinding.action == Some(*action) {
+            "press a key or button...".to_string()
+        } else {
+            input_map
+                .bindings(*action)
+                .iter()
+                .map(|binding| binding.to_string())
+                .collect::<Vec<_>>()
+                .join(" / ")
+        };
+
+        text.sections[0].value = format!("{:<10} {}", action.label(), bindings);
+    }
+
+    for mut text in q_targeting_text.iter_mut() {
+        text.sections[0].value = format!("{:<10} {}", "SPEARGUN", input_map.targeting.label());
+    }
+
+    for mut text in q_status_text.iter_mut() {
+        text.sections[0].value = rebinding.message.clone();
+    }
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct RebindingPlugin;
+
+impl Plugin for RebindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .init_resource::<ControlsBackState>()
+            .add_systems(OnEnter(GameState::Controls), setup_controls_menu)
+            .add_systems(
+                OnExit(GameState::Controls),
+                (save_input_map, cleanup_controls_menu),
+            )
+            .add_systems(
+                Update,
+                (
+                    click_controls_buttons,
+                    capture_binding.after(click_controls_buttons),
+                    update_binding_texts.after(capture_binding),
+                )
+                    .run_if(in_state(GameState::Controls)),
+            );
+    }
+}
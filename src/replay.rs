@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::CliArgs,
+    controls::{ControlEvent, ControlSet},
+    gameplay::{
+        progression::{event_choose_upgrade, ChooseUpgradeEvent},
+        rng::GameRng,
+        run::{EndRun, StartRun, StartRunSet},
+    },
+    input_map::{InputMap, Targeting},
+    GameState,
+};
+
+/// Replays are recorded and played back at this fixed timestep so physics and timers
+/// advance exactly the same way every time.
+pub const REPLAY_TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// ----
+// Data
+// ----
+
+/// Everything needed to reproduce a run: the rng seed, the speargun targeting mode the run
+/// started with, the control events sent in every frame of the `GamePlay` state and the
+/// upgrade picked on every level-up.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Replay {
+    pub seed: u64,
+    #[serde(default)]
+    pub targeting: Targeting,
+    pub frames: Vec<Vec<ControlEvent>>,
+    #[serde(default)]
+    pub upgrades: Vec<usize>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("could not read replay {}: {}", path.display(), e))?;
+
+        ron::from_str(&contents)
+            .map_err(|e| format!("could not parse replay {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = ron::to_string(self)
+            .map_err(|e| format!("could not serialize replay {}: {}", path.display(), e))?;
+
+        std::fs::write(path, contents)
+            .map_err(|e| format!("could not write replay {}: {}", path.display(), e))
+    }
+}
+
+// ---------
+// Resources
+// ---------
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+    pub replay: Replay,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub frame: usize,
+    pub upgrade: usize,
+}
+
+// -------
+// Systems
+// -------
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    game_rng: Res<GameRng>,
+    input_map: Res<InputMap>,
+) {
+    recorder.replay = Replay {
+        seed: game_rng.seed(),
+        targeting: input_map.targeting,
+        ..default()
+    };
+}
+
+fn record_controls(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut ev_control: EventReader<ControlEvent>,
+) {
+    let frame = ev_control.read().copied().collect();
+    recorder.replay.frames.push(frame);
+}
+
+fn save_recording(recorder: Res<ReplayRecorder>) {
+    match recorder.replay.save(&recorder.path) {
+        Ok(()) => info!(
+            "saved replay of {} frames to {}",
+            recorder.replay.frames.len(),
+            recorder.path.display()
+        ),
+        Err(e) => error!("{}", e),
+    }
+}
+
+fn save_recording_on_exit(
+    er_app_exit: EventReader<AppExit>,
+    recorder: Res<ReplayRecorder>,
+    state: Res<State<GameState>>,
+) {
+    if !er_app_exit.is_empty() && *state.get() == GameState::GamePlay {
+        save_recording(recorder);
+    }
+}
+
+fn start_playback(mut player: ResMut<ReplayPlayer>, mut input_map: ResMut<InputMap>) {
+    player.frame = 0;
+    player.upgrade = 0;
+    input_map.targeting = player.replay.targeting;
+}
+
+fn play_controls(mut player: ResMut<ReplayPlayer>, mut ev_control: EventWriter<ControlEvent>) {
+    let Some(frame) = player.replay.frames.get(player.frame) else {
+        if player.frame == player.replay.frames.len() {
+            info!("replay finished after {} frames", player.frame);
+            player.frame += 1;
+        }
+        return;
+    };
+
+    ev_control.send_batch(frame.iter().copied());
+    player.frame += 1;
+}
+
+fn play_upgrades(
+    mut player: ResMut<ReplayPlayer>,
+    mut ew_choose_upgrade: EventWriter<ChooseUpgradeEvent>,
+) {
+    // replays recorded before level-ups existed take the first upgrade
+    let index = player
+        .replay
+        .upgrades
+        .get(player.upgrade)
+        .copied()
+        .unwrap_or(0);
+
+    ew_choose_upgrade.send(ChooseUpgradeEvent { index });
+    player.upgrade += 1;
+}
+
+// ------
+// Plugin
+// ------
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let cli_args = app.world.resource::<CliArgs>().clone();
+
+        if let Some(path) = cli_args.replay {
+            // a missing or broken replay is a user error like a bad argument
+            let replay = Replay::load(&path).unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(2);
+            });
+
+            // the run has to start from the recorded seed
+            app.world.resource_mut::<CliArgs>().seed = Some(replay.seed);
+
+            app.insert_resource(ReplayPlayer {
+                replay,
+                frame: 0,
+                upgrade: 0,
+            })
+            .insert_resource(TimeUpdateStrategy::ManualDuration(REPLAY_TIMESTEP));
+        } else if let Some(path) = cli_args.record {
+            app.insert_resource(ReplayRecorder {
+                path,
+                replay: Replay::default(),
+            })
+            .insert_resource(TimeUpdateStrategy::ManualDuration(REPLAY_TIMESTEP));
+        }
+
+        app.configure_sets(
+            Update,
+            ControlSet::Input.run_if(not(resource_exists::<ReplayPlayer>())),
+        )
+        // Recording
+        .add_systems(
+            StartRun,
+            start_recording
+                .in_set(StartRunSet::Setup)
+                .run_if(resource_exists::<ReplayRecorder>()),
+        )
+        .add_systems(
+            EndRun,
+            save_recording.run_if(resource_exists::<ReplayRecorder>()),
+        )
+        .add_systems(
+            Update,
+            record_controls
+                .after(ControlSet::Input)
+                .run_if(in_state(GameState::GamePlay))
+                .run_if(resource_exists::<ReplayRecorder>()),
+        )
+        .add_systems(
+            Last,
+            save_recording_on_exit.run_if(resource_exists::<ReplayRecorder>()),
+        )
+        // Playback
+        .add_systems(
+            StartRun,
+            start_playback
+                .in_set(StartRunSet::Setup)
+                .run_if(resource_exists::<ReplayPlayer>()),
+        )
+        .add_systems(
+            Update,
+            play_controls
+                .after(ControlSet::Input)
+                .before(ControlSet::Apply)
+                .run_if(in_state(GameState::GamePlay))
+                .run_if(resource_exists::<ReplayPlayer>()),
+        )
+        .add_systems(
+            Update,
+            play_upgrades
+                .before(event_choose_upgrade)
+                .run_if(in_state(GameState::LevelUp))
+                .run_if(resource_exists::<ReplayPlayer>()),
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::{prelude::*, sprite::Anchor};
+use serde::Deserialize;
 
 use crate::loading::*;
 
@@ -12,18 +13,19 @@ pub const N_FRAMES_ATTACK: usize = 5;
 pub const PLAYER_ASSET_SHEET_1: &str = "sprites/alextime-1.png";
 pub const PLAYER_ASSET_SHEET_2: &str = "sprites/alextime-2.png";
 pub const MIERDA_ASSET_SHEET: &str = "sprites/mierda.png";
-pub const PSYCHIATRIST_1_ASSET_SHEET: &str = "sprites/psychiatrist-1.png";
-pub const PSYCHIATRIST_2_ASSET_SHEET: &str = "sprites/psychiatrist-2.png";
 pub const PIZZA_ASSET_SHEET: &str = "sprites/pizza.png";
 pub const LANTERN_ASSET_SHEET: &str = "sprites/lantern.png";
 pub const _SPEARGUN_ASSET_SHEET: &str = "sprites/speargun.png";
 pub const BIBORAN_ASSET_SHEET: &str = "sprites/biboran.png";
+pub const SPEED_BOOST_ASSET_SHEET: &str = "sprites/speed-boost.png";
+pub const SHIELD_ASSET_SHEET: &str = "sprites/shield.png";
+pub const BOMB_ASSET_SHEET: &str = "sprites/bomb.png";
 pub const PENDEJO_SPRITE_SHEETS: [(&str, AnimatedCharacterType); 2] = [
     ("sprites/pendejo-1.png", AnimatedCharacterType::Pendejo1),
     ("sprites/pendejo-2.png", AnimatedCharacterType::Pendejo2),
 ];
 
-#[derive(Copy, Clone, Reflect, Default, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Reflect, Default, Debug, PartialEq, Eq, Deserialize)]
 pub enum AnimatedCharacterType {
     #[default]
     Player,
@@ -3,21 +3,50 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct UIPlayerHealth;
 
+/// Fills up with the experience collected towards the next level.
+#[derive(Component)]
+pub struct UIExperienceBar;
+
+#[derive(Component)]
+pub struct UIPlayerLevel;
+
 #[derive(Component)]
 pub struct UIGameOver;
 
 #[derive(Component)]
 pub struct UIGameplayWave;
 
+#[derive(Component)]
+pub struct UIObjectives;
+
+#[derive(Component)]
+pub struct UIWeaponSlots;
+
 #[derive(Component)]
 pub struct UIWeaponName;
 
+/// Fills up as the cooldown of the weapon in `slot` of the inventory runs.
+#[derive(Component)]
+pub struct UIWeaponCooldown {
+    pub slot: usize,
+}
+
 #[derive(Component)]
 pub struct UIHighscore;
 
 #[derive(Component)]
 pub struct UIGamePlay;
 
+/// Boss health bar, only visible during a boss fight.
+#[derive(Component)]
+pub struct UIBossBar;
+
+#[derive(Component)]
+pub struct UIBossName;
+
+#[derive(Component)]
+pub struct UIBossHealth;
+
 pub(crate) fn despawn_ui(mut commands: Commands, query: Query<Entity, With<UIGamePlay>>) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
@@ -94,157 +123,167 @@ pub(crate) fn draw_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 .insert(UIPlayerHealth);
         });
 
-    // Weapon - Gun
+    // experience bar
     commands
         .spawn((
             NodeBundle {
                 style: Style {
+                    width: Val::Percent(50.0),
                     position_type: PositionType::Absolute,
                     justify_content: JustifyContent::FlexStart,
-                    bottom: Val::Px(25.0),
-                    right: Val::Px(5.0),
+                    bottom: Val::Px(10.0),
+                    left: Val::Px(20.0),
                     padding: UiRect {
                         right: Val::Px(15.0),
                         ..default()
                     },
-                    align_items: AlignItems::FlexStart,
+                    align_items: AlignItems::Center,
                     ..default()
                 },
                 ..default()
             },
             UIGamePlay,
-            Name::new("Weapon gun image"),
+            Name::new("ui experience bar"),
         ))
         .with_children(|parent| {
             parent.spawn((
-                NodeBundle {
+                TextBundle::from_section(
+                    "LV 1",
+                    TextStyle {
+                        font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::right(Val::Px(10.0)),
+                    ..default()
+                }),
+                UIPlayerLevel,
+            ));
+
+            parent
+                .spawn(NodeBundle {
                     style: Style {
-                        width: Val::Px(160.0),
-                        height: Val::Px(22.5),
-                        // margin: UiRect::top(Val::VMin(5.)),
+                        flex_grow: 1.0,
+                        height: Val::Px(8.0),
                         ..default()
                     },
-                    background_color: Color::WHITE.into(),
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
                     ..default()
-                },
-                UiImage::new(asset_server.load("sprites/speargun.png")),
-            ));
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::CYAN.into(),
+                            ..default()
+                        },
+                        UIExperienceBar,
+                    ));
+                });
         });
 
+    // Weapons, filled in from the weapon inventory
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                bottom: Val::Px(15.0),
+                right: Val::Px(5.0),
+                padding: UiRect {
+                    right: Val::Px(15.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ..default()
+        },
+        UIGamePlay,
+        UIWeaponSlots,
+        Name::new("Weapon slots"),
+    ));
+
+    // Wave
     commands
         .spawn((
             NodeBundle {
                 style: Style {
+                    width: Val::Percent(100.0),
                     position_type: PositionType::Absolute,
                     justify_content: JustifyContent::FlexStart,
-                    bottom: Val::Px(15.0),
-                    right: Val::Px(5.0),
-                    padding: UiRect {
-                        right: Val::Px(15.0),
-                        ..default()
-                    },
+                    top: Val::Px(20.0),
+                    left: Val::Px(20.0),
                     align_items: AlignItems::FlexStart,
                     ..default()
                 },
+                // visibility: Visibility::Hidden,
                 ..default()
             },
             UIGamePlay,
-            Name::new("Weapon name"),
+            Name::new("Wave Text"),
         ))
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "SPEARGUN",
+                    "ui wave text",
                     TextStyle {
                         font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
                         font_size: 20.0,
                         color: Color::WHITE,
                     },
                 ),
-                UIWeaponName,
-            ));
-        });
-
-    // Weapon - Machete
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    position_type: PositionType::Absolute,
-                    justify_content: JustifyContent::FlexStart,
-                    bottom: Val::Px(100.0),
-                    right: Val::Px(5.0),
-                    padding: UiRect {
-                        right: Val::Px(15.0),
-                        ..default()
-                    },
-                    align_items: AlignItems::FlexStart,
-                    ..default()
-                },
-                ..default()
-            },
-            UIGamePlay,
-            Name::new("Weapon machete  image"),
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                NodeBundle {
-                    style: Style {
-                        width: Val::Px(160.0),
-                        height: Val::Px(22.5),
-                        ..default()
-                    },
-                    background_color: Color::WHITE.into(),
-                    ..default()
-                },
-                UiImage::new(asset_server.load("sprites/machete.png")),
+                UIGameplayWave,
             ));
         });
 
+    // Objectives
     commands
         .spawn((
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
                     justify_content: JustifyContent::FlexStart,
-                    bottom: Val::Px(85.0),
-                    right: Val::Px(5.0),
-                    padding: UiRect {
-                        right: Val::Px(15.0),
-                        ..default()
-                    },
+                    top: Val::Px(50.0),
+                    left: Val::Px(20.0),
                     align_items: AlignItems::FlexStart,
                     ..default()
                 },
                 ..default()
             },
             UIGamePlay,
-            Name::new("Weapon name"),
+            Name::new("Objectives Text"),
         ))
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "MACHETE",
+                    "",
                     TextStyle {
                         font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
-                        font_size: 20.0,
+                        font_size: 16.0,
                         color: Color::WHITE,
                     },
                 ),
-                UIWeaponName,
+                UIObjectives,
             ));
         });
 
-    // Wave
+    // Highscore
     commands
         .spawn((
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.0),
                     position_type: PositionType::Absolute,
-                    justify_content: JustifyContent::FlexStart,
+                    justify_content: JustifyContent::FlexEnd,
                     top: Val::Px(20.0),
-                    left: Val::Px(20.0),
+                    right: Val::Px(20.0),
                     align_items: AlignItems::FlexStart,
                     ..default()
                 },
@@ -257,47 +296,73 @@ pub(crate) fn draw_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "ui wave text",
+                    "SCORE: 0",
                     TextStyle {
                         font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
-                        font_size: 20.0,
+                        font_size: 30.0,
                         color: Color::WHITE,
                     },
                 ),
-                UIGameplayWave,
+                UIHighscore,
             ));
         });
 
-    // Highscore
+    // Boss health bar
     commands
         .spawn((
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.0),
                     position_type: PositionType::Absolute,
-                    justify_content: JustifyContent::FlexEnd,
-                    top: Val::Px(20.0),
-                    right: Val::Px(20.0),
-                    align_items: AlignItems::FlexStart,
+                    flex_direction: FlexDirection::Column,
+                    top: Val::Px(60.0),
+                    align_items: AlignItems::Center,
                     ..default()
                 },
-                // visibility: Visibility::Hidden,
+                visibility: Visibility::Hidden,
                 ..default()
             },
             UIGamePlay,
-            Name::new("Wave Text"),
+            UIBossBar,
+            Name::new("ui boss healthbar"),
         ))
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "SCORE: 0",
+                    "",
                     TextStyle {
                         font: asset_server.load("fonts/PixeloidMono-d94EV.ttf"),
-                        font_size: 30.0,
+                        font_size: 20.0,
                         color: Color::WHITE,
                     },
                 ),
-                UIHighscore,
+                UIBossName,
             ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(40.0),
+                        height: Val::Px(16.0),
+                        margin: UiRect::top(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::PURPLE.into(),
+                            ..default()
+                        },
+                        UIBossHealth,
+                    ));
+                });
         });
 }